@@ -0,0 +1,87 @@
+//! Compare size and time of the rate file codecs on real maps.
+//!
+//! Usage: `cargo bench --bench compression -- <dir with .osu files>`
+//! (defaults to `PENDORA_BENCH_MAPS` or `benches/maps`).
+
+use pendora::utils::rate::compression::{CompressionCodec, CompressionManager, CompressionOptions};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+fn levels(codec: CompressionCodec) -> Vec<i32> {
+    match codec {
+        CompressionCodec::Brotli => vec![1, 4, 6, 9, 11],
+        CompressionCodec::Zstd => vec![1, 3, 9, 15, 19],
+        CompressionCodec::Gzip => vec![1, 6, 9],
+        CompressionCodec::None => vec![0],
+    }
+}
+
+fn load_maps() -> Vec<String> {
+    let dir = std::env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with("--"))
+        .or_else(|| std::env::var("PENDORA_BENCH_MAPS").ok())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("benches/maps"));
+
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        eprintln!("No maps directory found at {}", dir.display());
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "osu"))
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .collect()
+}
+
+fn main() {
+    let maps = load_maps();
+    if maps.is_empty() {
+        return;
+    }
+
+    let original_size: usize = maps.iter().map(|m| m.len()).sum();
+    println!("{} maps, {} bytes uncompressed", maps.len(), original_size);
+    println!(
+        "{:<8} {:>5} {:>12} {:>8} {:>12} {:>12}",
+        "codec", "level", "size", "ratio", "compress", "decompress"
+    );
+
+    for codec in CompressionCodec::ALL {
+        for level in levels(codec) {
+            let options = CompressionOptions {
+                codec,
+                level: Some(level),
+            };
+
+            let mut size = 0;
+            let mut compress_time = Duration::ZERO;
+            let mut decompress_time = Duration::ZERO;
+
+            for map in &maps {
+                let start = Instant::now();
+                let result = CompressionManager::compress_string(map, &options).unwrap();
+                compress_time += start.elapsed();
+                size += result.compressed_size;
+
+                let start = Instant::now();
+                let decompressed =
+                    CompressionManager::decompress(&result.compressed_data, codec).unwrap();
+                decompress_time += start.elapsed();
+                assert_eq!(decompressed.len(), map.len());
+            }
+
+            println!(
+                "{:<8} {:>5} {:>12} {:>7.1}% {:>10.1}ms {:>10.1}ms",
+                codec.to_string(),
+                level,
+                size,
+                size as f64 / original_size as f64 * 100.0,
+                compress_time.as_secs_f64() * 1000.0,
+                decompress_time.as_secs_f64() * 1000.0
+            );
+        }
+    }
+}
@@ -0,0 +1,4 @@
+-- OD/HP of each rated file, after the adjustments of RATE_OD and RATE_HP.
+-- NULL for rates inserted before this migration.
+ALTER TABLE rates ADD COLUMN IF NOT EXISTS od NUMERIC(4, 2);
+ALTER TABLE rates ADD COLUMN IF NOT EXISTS hp NUMERIC(4, 2);
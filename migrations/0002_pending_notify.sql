@@ -0,0 +1,24 @@
+-- Wake the workers LISTENing on pending_beatmap for every hash added to the queue,
+-- whichever process inserted it. The payload is the hash.
+CREATE OR REPLACE FUNCTION notify_pending_beatmap() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('pending_beatmap', NEW.osu_hash);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Created only when missing: no DROP, so there is never a window without the trigger
+-- (CREATE OR REPLACE TRIGGER would need PostgreSQL 14).
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_trigger
+        WHERE tgname = 'pending_beatmap_notify'
+          AND tgrelid = 'pending_beatmap'::regclass
+    ) THEN
+        CREATE TRIGGER pending_beatmap_notify
+            AFTER INSERT ON pending_beatmap
+            FOR EACH ROW EXECUTE FUNCTION notify_pending_beatmap();
+    END IF;
+END;
+$$;
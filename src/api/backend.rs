@@ -0,0 +1,34 @@
+use crate::errors::OsuApiError;
+use async_trait::async_trait;
+use rosu_v2::prelude::{BeatmapExtended, BeatmapsetExtended, RankStatus};
+
+/// Source of beatmap metadata and `.osu` files used by the worker
+///
+/// Implemented by the real osu! API client and by a fixture-backed client for offline use.
+#[async_trait]
+pub trait OsuApi: Send + Sync {
+    // checksum = hash of the beatmap file
+    async fn beatmap_by_checksum(&self, checksum: String) -> Result<BeatmapExtended, OsuApiError>;
+
+    async fn beatmap_by_osu_id(&self, osu_id: i32) -> Result<BeatmapExtended, OsuApiError>;
+
+    /// Same as `beatmap_by_osu_id` but never answered from a cache, for data that changes
+    /// such as the rank status. Implementations with a cache must override it.
+    async fn fresh_beatmap_by_osu_id(&self, osu_id: i32) -> Result<BeatmapExtended, OsuApiError> {
+        self.beatmap_by_osu_id(osu_id).await
+    }
+
+    /// Beatmapset with all its difficulties (`maps`)
+    async fn beatmapset_by_osu_id(&self, mapset_id: u32)
+        -> Result<BeatmapsetExtended, OsuApiError>;
+
+    /// Page (starting at 1) of mania beatmapsets with the given status, most recently ranked first
+    async fn search_mania_beatmapsets(
+        &self,
+        status: RankStatus,
+        page: u32,
+    ) -> Result<Vec<BeatmapsetExtended>, OsuApiError>;
+
+    /// Raw content of the `.osu` file of a beatmap
+    async fn osu_file(&self, map_id: u32) -> Result<String, OsuApiError>;
+}
@@ -0,0 +1,131 @@
+use rosu_v2::prelude::{BeatmapExtended, RankStatus};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// Settings of the on-disk osu! API response cache
+#[derive(Debug, Clone)]
+pub struct ApiCacheOptions {
+    /// Cache directory, None disables the cache
+    pub dir: Option<PathBuf>,
+    /// TTL for maps whose status won't change anymore (ranked, approved, loved)
+    pub final_ttl: Duration,
+    /// TTL for every other status (pending, wip, qualified, graveyard)
+    pub short_ttl: Duration,
+}
+
+impl Default for ApiCacheOptions {
+    fn default() -> Self {
+        Self {
+            dir: Some(PathBuf::from("cache/osu_api")),
+            final_ttl: Duration::from_secs(30 * 24 * 3600),
+            short_ttl: Duration::from_secs(3600),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: u64,
+    beatmap: BeatmapExtended,
+}
+
+/// Beatmap lookups cached on disk by checksum and map id, with a TTL per rank status
+pub struct ApiCache {
+    dir: PathBuf,
+    options: ApiCacheOptions,
+}
+
+impl ApiCache {
+    pub fn new(options: ApiCacheOptions) -> Option<Self> {
+        let dir = options.dir.clone()?;
+        Some(Self { dir, options })
+    }
+
+    pub async fn get_by_checksum(&self, checksum: &str) -> Option<BeatmapExtended> {
+        self.read(&self.checksum_path(checksum)?).await
+    }
+
+    pub async fn get_by_map_id(&self, map_id: u32) -> Option<BeatmapExtended> {
+        self.read(&self.map_id_path(map_id)).await
+    }
+
+    /// Store a beatmap under both its map id and its checksum
+    pub async fn store(&self, beatmap: &BeatmapExtended) {
+        let entry = CacheEntry {
+            fetched_at: now_secs(),
+            beatmap: beatmap.clone(),
+        };
+
+        let data = match serde_json::to_vec(&entry) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Failed to serialize cache entry: {}", e);
+                return;
+            }
+        };
+
+        let mut paths = vec![self.map_id_path(beatmap.map_id)];
+        if let Some(path) = beatmap
+            .checksum
+            .as_deref()
+            .and_then(|checksum| self.checksum_path(checksum))
+        {
+            paths.push(path);
+        }
+
+        for path in paths {
+            if let Err(e) = write_file(&path, &data).await {
+                warn!("Failed to write cache entry {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    fn ttl(&self, status: RankStatus) -> Duration {
+        match status {
+            RankStatus::Ranked | RankStatus::Approved | RankStatus::Loved => self.options.final_ttl,
+            _ => self.options.short_ttl,
+        }
+    }
+
+    async fn read(&self, path: &Path) -> Option<BeatmapExtended> {
+        let data = tokio::fs::read(path).await.ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&data).ok()?;
+
+        let age = Duration::from_secs(now_secs().saturating_sub(entry.fetched_at));
+        if age > self.ttl(entry.beatmap.status) {
+            debug!("Cache entry expired: {}", path.display());
+            return None;
+        }
+
+        debug!("Cache hit: {}", path.display());
+        Some(entry.beatmap)
+    }
+
+    fn checksum_path(&self, checksum: &str) -> Option<PathBuf> {
+        // Only md5 hex digests are valid keys, which also keeps the path inside the cache
+        if checksum.is_empty() || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some(self.dir.join("checksum").join(format!("{}.json", checksum)))
+    }
+
+    fn map_id_path(&self, map_id: u32) -> PathBuf {
+        self.dir.join("map").join(format!("{}.json", map_id))
+    }
+}
+
+async fn write_file(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, data).await
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
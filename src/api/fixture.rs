@@ -0,0 +1,114 @@
+use crate::api::backend::OsuApi;
+use crate::errors::OsuApiError;
+use async_trait::async_trait;
+use rosu_v2::prelude::{BeatmapExtended, BeatmapsetExtended, RankStatus};
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+
+/// osu! API backed by JSON fixtures on disk, for offline runs and tests
+///
+/// Layout:
+/// - `beatmaps/{map_id}.json`: `BeatmapExtended` as returned by the API
+/// - `beatmapsets/{mapset_id}.json`: `BeatmapsetExtended`, attached when the beatmap has no mapset
+/// - `osu/{map_id}.osu`: the `.osu` file
+pub struct FixtureOsuApi {
+    dir: PathBuf,
+}
+
+impl FixtureOsuApi {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    async fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, OsuApiError> {
+        let data = tokio::fs::read(path)
+            .await
+            .map_err(|_| OsuApiError::NotFound(path.display().to_string()))?;
+        serde_json::from_slice(&data)
+            .map_err(|e| OsuApiError::Other(format!("{}: {}", path.display(), e)))
+    }
+
+    async fn with_mapset(&self, mut beatmap: BeatmapExtended) -> BeatmapExtended {
+        if beatmap.mapset.is_none() {
+            let path = self
+                .dir
+                .join("beatmapsets")
+                .join(format!("{}.json", beatmap.mapset_id));
+            beatmap.mapset = Self::read_json::<BeatmapsetExtended>(&path).await.ok();
+        }
+        beatmap
+    }
+}
+
+#[async_trait]
+impl OsuApi for FixtureOsuApi {
+    async fn beatmap_by_checksum(&self, checksum: String) -> Result<BeatmapExtended, OsuApiError> {
+        let mut entries = tokio::fs::read_dir(self.dir.join("beatmaps"))
+            .await
+            .map_err(|_| OsuApiError::NotFound(format!("beatmap checksum {}", checksum)))?;
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(beatmap) = Self::read_json::<BeatmapExtended>(&entry.path()).await else {
+                continue;
+            };
+            if beatmap.checksum.as_deref() == Some(checksum.as_str()) {
+                return Ok(self.with_mapset(beatmap).await);
+            }
+        }
+
+        Err(OsuApiError::NotFound(format!(
+            "beatmap checksum {}",
+            checksum
+        )))
+    }
+
+    async fn beatmap_by_osu_id(&self, osu_id: i32) -> Result<BeatmapExtended, OsuApiError> {
+        let path = self.dir.join("beatmaps").join(format!("{}.json", osu_id));
+        let beatmap = Self::read_json::<BeatmapExtended>(&path).await?;
+        Ok(self.with_mapset(beatmap).await)
+    }
+
+    async fn beatmapset_by_osu_id(
+        &self,
+        mapset_id: u32,
+    ) -> Result<BeatmapsetExtended, OsuApiError> {
+        let path = self
+            .dir
+            .join("beatmapsets")
+            .join(format!("{}.json", mapset_id));
+        Self::read_json::<BeatmapsetExtended>(&path).await
+    }
+
+    /// All fixture mapsets with the given status on the first page, most recent first
+    async fn search_mania_beatmapsets(
+        &self,
+        status: RankStatus,
+        page: u32,
+    ) -> Result<Vec<BeatmapsetExtended>, OsuApiError> {
+        let mut mapsets = Vec::new();
+        if page > 1 {
+            return Ok(mapsets);
+        }
+
+        let Ok(mut entries) = tokio::fs::read_dir(self.dir.join("beatmapsets")).await else {
+            return Ok(mapsets);
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if let Ok(mapset) = Self::read_json::<BeatmapsetExtended>(&entry.path()).await {
+                if mapset.status == status {
+                    mapsets.push(mapset);
+                }
+            }
+        }
+
+        mapsets.sort_by_key(|m| std::cmp::Reverse(m.ranked_date.unwrap_or(m.last_updated)));
+        Ok(mapsets)
+    }
+
+    async fn osu_file(&self, map_id: u32) -> Result<String, OsuApiError> {
+        let path = self.dir.join("osu").join(format!("{}.osu", map_id));
+        tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|_| OsuApiError::NotFound(path.display().to_string()))
+    }
+}
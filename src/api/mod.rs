@@ -1 +1,7 @@
+pub mod backend;
+pub mod cache;
+pub mod fixture;
 pub mod osu;
+pub mod rate_limit;
+
+pub use backend::OsuApi;
@@ -1,27 +1,303 @@
+use crate::api::backend::OsuApi;
+use crate::api::cache::{ApiCache, ApiCacheOptions};
+use crate::api::rate_limit::TokenBucket;
+use crate::errors::OsuApiError;
+use crate::utils::build_file_path;
 use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
 use rosu_v2::prelude::*;
+use std::future::{Future, IntoFuture};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+
+/// OAuth client of the osu! API
+#[derive(Debug, Clone)]
+pub struct OsuCredentials {
+    pub client_id: u64,
+    pub client_secret: String,
+}
+
+/// Rate limiting and retry settings for the osu! API client
+#[derive(Debug, Clone)]
+pub struct OsuApiOptions {
+    /// Only optional when `fixtures_dir` is set
+    pub credentials: Option<OsuCredentials>,
+    pub requests_per_minute: u32,
+    /// Retries for transient errors (429, 5xx, timeouts)
+    pub max_retries: u32,
+    /// Base delay of the exponential backoff, jitter is added on top
+    pub retry_base_delay_ms: u64,
+    /// Upper bound of a `Retry-After` delay, osu! being trusted only up to it
+    pub max_retry_delay: Duration,
+    /// Timeout of a single `.osu` download
+    pub download_timeout: Duration,
+    pub cache: ApiCacheOptions,
+    /// Serve beatmaps from JSON fixtures instead of the osu! API
+    pub fixtures_dir: Option<PathBuf>,
+}
+
+impl Default for OsuApiOptions {
+    fn default() -> Self {
+        Self {
+            credentials: None,
+            requests_per_minute: 60,
+            max_retries: 3,
+            retry_base_delay_ms: 1000,
+            max_retry_delay: Duration::from_secs(60),
+            download_timeout: Duration::from_secs(30),
+            cache: ApiCacheOptions::default(),
+            fixtures_dir: None,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct OsuApiService {
     client: Arc<Osu>,
+    /// `.osu` downloads, which are not part of the osu! API
+    http: reqwest::Client,
+    limiter: Arc<TokenBucket>,
+    cache: Option<Arc<ApiCache>>,
+    options: OsuApiOptions,
 }
 
 impl OsuApiService {
-    pub async fn new(client_id: String, client_secret: String) -> Result<Self> {
-        let client = Arc::new(Osu::new(client_id.parse::<u64>().unwrap(), client_secret).await?);
+    pub async fn new(credentials: &OsuCredentials, options: OsuApiOptions) -> Result<Self> {
+        let client =
+            Arc::new(Osu::new(credentials.client_id, credentials.client_secret.clone()).await?);
+        let limiter = Arc::new(TokenBucket::per_minute(options.requests_per_minute));
+        let cache = ApiCache::new(options.cache.clone()).map(Arc::new);
+
+        Ok(Self {
+            client,
+            http: reqwest::Client::new(),
+            limiter,
+            cache,
+            options,
+        })
+    }
+
+    async fn store(&self, beatmap: &BeatmapExtended) {
+        if let Some(cache) = &self.cache {
+            cache.store(beatmap).await;
+        }
+    }
+
+    /// Rate-limited osu! API request, see `retry`
+    async fn with_retry<T, F, Fut>(&self, context: &str, request: F) -> Result<T, OsuApiError>
+    where
+        F: Fn() -> Fut,
+        Fut: IntoFuture<Output = Result<T, OsuError>>,
+    {
+        let request = &request;
+        self.retry(move || async move {
+            request()
+                .await
+                .map_err(|e| OsuApiError::from_osu_error(e, context))
+        })
+        .await
+    }
+
+    /// Rate-limited request retried on transient errors, after the `Retry-After` delay
+    /// when osu! gives one, otherwise with exponential backoff and jitter
+    async fn retry<T, F, Fut>(&self, request: F) -> Result<T, OsuApiError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, OsuApiError>>,
+    {
+        let mut attempt = 0;
+
+        loop {
+            self.limiter.acquire().await;
+
+            let error = match request().await {
+                Ok(value) => return Ok(value),
+                Err(e) => e,
+            };
+
+            if !error.is_transient() || attempt >= self.options.max_retries {
+                return Err(error);
+            }
+
+            let delay = match error.retry_after() {
+                Some(retry_after) => retry_after.min(self.options.max_retry_delay),
+                None => {
+                    let base = self.options.retry_base_delay_ms * 2u64.pow(attempt);
+                    let jitter = rand::random_range(0..=self.options.retry_base_delay_ms);
+                    Duration::from_millis(base + jitter)
+                }
+            };
+            tracing::warn!(
+                "{} (attempt {}/{}), retrying in {:?}",
+                error,
+                attempt + 1,
+                self.options.max_retries,
+                delay
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Single download of a `.osu` file, 429 carrying its `Retry-After`
+    async fn download_osu_file(&self, map_id: u32, context: &str) -> Result<String, OsuApiError> {
+        let transient = |e: reqwest::Error| OsuApiError::Transient(format!("{}: {}", context, e));
+
+        let response = self
+            .http
+            .get(build_file_path(map_id))
+            .send()
+            .await
+            .map_err(transient)?;
+
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
+            return Err(OsuApiError::RateLimited {
+                context: context.to_string(),
+                retry_after,
+            });
+        }
+        if status == StatusCode::NOT_FOUND {
+            return Err(OsuApiError::NotFound(context.to_string()));
+        }
+        if status.is_server_error() {
+            return Err(OsuApiError::Transient(format!("{}: {}", context, status)));
+        }
+        if !status.is_success() {
+            return Err(OsuApiError::Other(format!("{}: {}", context, status)));
+        }
+
+        response.text().await.map_err(transient)
+    }
+}
 
-        Ok(Self { client })
+/// `Retry-After` value, either a number of seconds or an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
     }
 
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(delay.to_std().unwrap_or(Duration::ZERO))
+}
+
+#[async_trait]
+impl OsuApi for OsuApiService {
     // checksum = hash of the beatmap file
-    pub async fn beatmap_by_checksum(&self, checksum: String) -> Result<BeatmapExtended> {
-        let beatmap = self.client.beatmap().checksum(checksum).await?;
+    async fn beatmap_by_checksum(&self, checksum: String) -> Result<BeatmapExtended, OsuApiError> {
+        if let Some(cache) = &self.cache {
+            if let Some(beatmap) = cache.get_by_checksum(&checksum).await {
+                return Ok(beatmap);
+            }
+        }
+
+        let context = format!("beatmap checksum {}", checksum);
+        let beatmap = self
+            .with_retry(&context, || {
+                self.client.beatmap().checksum(checksum.clone())
+            })
+            .await?;
+        self.store(&beatmap).await;
         Ok(beatmap)
     }
 
-    pub async fn beatmap_by_osu_id(&self, osu_id: i32) -> Result<BeatmapExtended> {
-        let beatmap = self.client.beatmap().map_id(osu_id as u32).await?;
+    async fn beatmap_by_osu_id(&self, osu_id: i32) -> Result<BeatmapExtended, OsuApiError> {
+        if let Some(cache) = &self.cache {
+            if let Some(beatmap) = cache.get_by_map_id(osu_id as u32).await {
+                return Ok(beatmap);
+            }
+        }
+
+        self.fresh_beatmap_by_osu_id(osu_id).await
+    }
+
+    /// Skips the cache lookup, the answer still replaces the cached entry
+    async fn fresh_beatmap_by_osu_id(&self, osu_id: i32) -> Result<BeatmapExtended, OsuApiError> {
+        let context = format!("beatmap id {}", osu_id);
+        let beatmap = self
+            .with_retry(&context, || self.client.beatmap().map_id(osu_id as u32))
+            .await?;
+        self.store(&beatmap).await;
         Ok(beatmap)
     }
+
+    async fn beatmapset_by_osu_id(
+        &self,
+        mapset_id: u32,
+    ) -> Result<BeatmapsetExtended, OsuApiError> {
+        let context = format!("beatmapset id {}", mapset_id);
+        self.with_retry(&context, || self.client.beatmapset(mapset_id))
+            .await
+    }
+
+    async fn search_mania_beatmapsets(
+        &self,
+        status: RankStatus,
+        page: u32,
+    ) -> Result<Vec<BeatmapsetExtended>, OsuApiError> {
+        let context = format!("beatmapset search {:?} page {}", status, page);
+        let result = self
+            .with_retry(&context, || {
+                self.client
+                    .beatmapset_search()
+                    .mode(GameMode::Mania)
+                    .status(Some(status))
+                    .sort(BeatmapsetSearchSort::RankedDate, true)
+                    .page(page as usize)
+            })
+            .await?;
+        Ok(result.mapsets)
+    }
+
+    async fn osu_file(&self, map_id: u32) -> Result<String, OsuApiError> {
+        let context = &format!("osu file {}", map_id);
+        self.retry(move || async move {
+            tokio::time::timeout(
+                self.options.download_timeout,
+                self.download_osu_file(map_id, context),
+            )
+            .await
+            .map_err(|_| OsuApiError::Transient(format!("{}: timed out", context)))?
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_retry_after;
+    use std::time::Duration;
+
+    #[test]
+    fn retry_after_in_seconds() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_as_http_date() {
+        let date = (chrono::Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+        let delay = parse_retry_after(&date).unwrap();
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+
+        // A date already passed means retrying right away
+        let past = parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT");
+        assert_eq!(past, Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn invalid_retry_after() {
+        assert_eq!(parse_retry_after("soon"), None);
+        assert_eq!(parse_retry_after("-5"), None);
+    }
 }
@@ -0,0 +1,79 @@
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Token bucket limiting the number of requests sent per minute
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn per_minute(requests_per_minute: u32) -> Self {
+        let capacity = requests_per_minute.max(1) as f64;
+        Self {
+            capacity,
+            refill_per_sec: capacity / 60.0,
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a token is available and consume it
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().await;
+                self.refill(&mut state);
+
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+
+                Duration::from_secs_f64((1.0 - state.tokens) / self.refill_per_sec)
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Consume a token if one is available, without waiting
+    pub async fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().await;
+        self.refill(&mut state);
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether the bucket has refilled completely, it then behaves like a new one.
+    /// A bucket locked by a concurrent request is not considered full.
+    pub fn is_full(&self) -> bool {
+        match self.state.try_lock() {
+            Ok(mut state) => {
+                self.refill(&mut state);
+                state.tokens >= self.capacity
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn refill(&self, state: &mut BucketState) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        state.last_refill = now;
+    }
+}
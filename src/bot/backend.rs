@@ -0,0 +1,62 @@
+use crate::api::OsuApi;
+use crate::core::enqueue::enqueue_hash;
+use crate::core::query::recommend::{recommend, RecommendRequest, Recommendations};
+use crate::core::rating::local::{rate_osu_file_blocking, BeatmapRating};
+use crate::utils::rate::options::RateOptions;
+use anyhow::Result;
+use async_trait::async_trait;
+use db::db::DatabaseManager;
+use db::models::beatmaps::beatmap::BeatmapRow;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Everything the slash commands need from the library
+///
+/// Implemented on top of the database and the osu! API, and by mocks in tests.
+#[async_trait]
+pub trait BotBackend: Send + Sync {
+    /// Raw content of the `.osu` file of a beatmap
+    async fn osu_file(&self, map_id: u32) -> Result<String>;
+
+    /// Ratings of a `.osu` file at every configured rate
+    async fn rate(&self, osu_map: String) -> Result<BeatmapRating>;
+
+    async fn recommend(&self, request: &RecommendRequest) -> Result<Recommendations>;
+
+    /// Whether a beatmap with this `.osu` hash is already processed
+    async fn is_processed(&self, osu_hash: &str) -> Result<bool>;
+
+    async fn enqueue(&self, osu_hash: &str) -> Result<()>;
+}
+
+pub struct LibraryBackend {
+    pub database: DatabaseManager,
+    pub osu_api: Arc<dyn OsuApi>,
+    pub rate: RateOptions,
+    /// Limits concurrent `/rate` calculations, see `server::calculation_slots`
+    pub calculations: Arc<Semaphore>,
+}
+
+#[async_trait]
+impl BotBackend for LibraryBackend {
+    async fn osu_file(&self, map_id: u32) -> Result<String> {
+        Ok(self.osu_api.osu_file(map_id).await?)
+    }
+
+    async fn rate(&self, osu_map: String) -> Result<BeatmapRating> {
+        let _permit = self.calculations.acquire().await?;
+        Ok(rate_osu_file_blocking(osu_map, self.rate.clone()).await?)
+    }
+
+    async fn recommend(&self, request: &RecommendRequest) -> Result<Recommendations> {
+        Ok(recommend(&self.database, request).await?)
+    }
+
+    async fn is_processed(&self, osu_hash: &str) -> Result<bool> {
+        Ok(BeatmapRow::exists_by_hash(self.database.get_pool(), osu_hash).await?)
+    }
+
+    async fn enqueue(&self, osu_hash: &str) -> Result<()> {
+        Ok(enqueue_hash(&self.database, osu_hash).await?)
+    }
+}
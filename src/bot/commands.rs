@@ -0,0 +1,421 @@
+use crate::bot::backend::BotBackend;
+use crate::core::query::recommend::{RecommendRequest, SkillsetVector};
+use crate::core::rating::skillset::Skillset;
+use anyhow::{anyhow, Result};
+use dto::models::beatmaps::full::types::Beatmapset;
+use std::sync::Arc;
+
+/// Discord message length limit
+const MAX_MESSAGE_LEN: usize = 2000;
+const RECOMMEND_LIMIT: u32 = 10;
+
+/// Slash commands, independent of the Discord gateway so they can be driven by a mock
+#[derive(Debug, Clone, PartialEq)]
+pub enum BotCommand {
+    /// `/rate <link>`
+    Rate { link: String },
+    /// `/recommend <msd> <pattern> [skillsets]`, skillsets separated by commas or spaces
+    Recommend {
+        msd: f64,
+        pattern: String,
+        skillsets: Option<String>,
+    },
+    /// `/queue <hash>`
+    Queue { hash: String },
+}
+
+pub struct BotCommands {
+    pub backend: Arc<dyn BotBackend>,
+}
+
+impl BotCommands {
+    /// Run a command and build the reply, errors are replied as text
+    pub async fn handle(&self, command: BotCommand) -> String {
+        let reply = match command {
+            BotCommand::Rate { link } => self.rate(&link).await,
+            BotCommand::Recommend {
+                msd,
+                pattern,
+                skillsets,
+            } => self.recommend(msd, &pattern, skillsets.as_deref()).await,
+            BotCommand::Queue { hash } => self.queue(&hash).await,
+        };
+
+        truncate(reply.unwrap_or_else(|e| format!("Error: {}", e)))
+    }
+
+    async fn rate(&self, link: &str) -> Result<String> {
+        let map_id =
+            beatmap_id_from_link(link).ok_or_else(|| anyhow!("not an osu! beatmap link"))?;
+        let osu_map = self.backend.osu_file(map_id).await?;
+        let rating = self.backend.rate(osu_map).await?;
+
+        let mut reply = format!("Main pattern: {}\n```\n", rating.main_pattern);
+        reply.push_str(&format!(
+            "{:>5} {:>8} {:>8} {:>8}\n",
+            "rate", "etterna", "sunnyxxy", "osu"
+        ));
+        for rated in &rating.rates {
+            let rates = &rated.rates;
+            let value = |rating_type: &str| {
+                rates
+                    .rating
+                    .iter()
+                    .find(|r| r.rating_type == rating_type)
+                    .map(|r| r.rating)
+                    .unwrap_or_default()
+            };
+            reply.push_str(&format!(
+                "{:>4.1}x {:>8.2} {:>8.2} {:>8.2}\n",
+                rates.centirate as f64 / 100.0,
+                value("etterna"),
+                value("sunnyxxy"),
+                value("osu")
+            ));
+        }
+        reply.push_str("```");
+
+        Ok(reply)
+    }
+
+    async fn recommend(&self, msd: f64, pattern: &str, skillsets: Option<&str>) -> Result<String> {
+        let request = recommend_request(msd, pattern, skillsets)?;
+        let recommendations = self.backend.recommend(&request).await?;
+
+        if recommendations.results.is_empty() {
+            return Ok(format!(
+                "No processed {} map close to {:.1} MSD",
+                request.pattern.unwrap_or_default(),
+                msd
+            ));
+        }
+
+        Ok(recommendations
+            .results
+            .iter()
+            .map(|recommendation| {
+                let hit = &recommendation.hit;
+                format!(
+                    "{} - {} [{}] {:.1}x: {:.2}{}",
+                    hit.artist,
+                    hit.title,
+                    hit.difficulty,
+                    hit.centirate as f64 / 100.0,
+                    hit.rating,
+                    hit.beatmap_osu_id
+                        .map(|id| format!(" <https://osu.ppy.sh/b/{}>", id))
+                        .unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    async fn queue(&self, hash: &str) -> Result<String> {
+        if hash.len() != 32 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow!("expected the MD5 hash of a .osu file"));
+        }
+
+        let hash = hash.to_ascii_lowercase();
+        if self.backend.is_processed(&hash).await? {
+            return Ok(format!("`{}` is already processed", hash));
+        }
+
+        self.backend.enqueue(&hash).await?;
+        Ok(format!("`{}` added to the queue", hash))
+    }
+}
+
+/// Etterna request for `pattern` maps targeting `msd` on the given skillsets, all seven
+/// when none is given
+fn recommend_request(msd: f64, pattern: &str, skillsets: Option<&str>) -> Result<RecommendRequest> {
+    let pattern = pattern_name(pattern).ok_or_else(|| anyhow!("expected a pattern"))?;
+    let mut weights = SkillsetVector::default();
+    let requested = skillsets
+        .unwrap_or_default()
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|name| !name.is_empty())
+        .map(|name| name.parse::<Skillset>().map_err(|e| anyhow!(e)))
+        .collect::<Result<Vec<_>>>()?;
+    if !requested.is_empty() {
+        weights = SkillsetVector::splat(0.0);
+        for skillset in requested {
+            weights.set(skillset, 1.0);
+        }
+    }
+
+    let request = RecommendRequest {
+        rating_type: "etterna".to_string(),
+        target: Some(SkillsetVector::splat(msd)),
+        recent_hashes: Vec::new(),
+        weights,
+        keys: None,
+        pattern: Some(pattern),
+        limit: Some(RECOMMEND_LIMIT),
+    };
+    request.validate().map_err(|e| anyhow!(e))?;
+    Ok(request)
+}
+
+/// Pattern as stored in `main_pattern`: `LN`, `Hybrid` or a lowercase skillset
+fn pattern_name(pattern: &str) -> Option<String> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        None
+    } else if pattern.eq_ignore_ascii_case("ln") {
+        Some("LN".to_string())
+    } else if pattern.eq_ignore_ascii_case("hybrid") {
+        Some("Hybrid".to_string())
+    } else {
+        Some(pattern.to_ascii_lowercase())
+    }
+}
+
+/// Beatmap id of `osu.ppy.sh/b/<id>`, `/beatmaps/<id>` or `/beatmapsets/<set>#mania/<id>` links
+pub fn beatmap_id_from_link(link: &str) -> Option<u32> {
+    let link = link.trim().trim_end_matches('/');
+
+    if let Some((_, id)) = link.split_once('#') {
+        return id.rsplit('/').next()?.parse().ok();
+    }
+
+    let mut segments = link.rsplit('/');
+    let id = segments.next()?.split('?').next()?;
+    match segments.next()? {
+        "b" | "beatmaps" => id.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Message posted to the results channel once a beatmap is processed
+pub fn format_processed(beatmapset: &Beatmapset) -> String {
+    let mut message = format!(
+        "Processed **{} - {}** ({})",
+        beatmapset.artist, beatmapset.title, beatmapset.creator
+    );
+
+    for beatmap in &beatmapset.beatmaps {
+        let etterna = beatmap
+            .rates
+            .iter()
+            .find(|rates| rates.centirate == 100)
+            .and_then(|rates| rates.rating.iter().find(|r| r.rating_type == "etterna"))
+            .map(|r| format!("{:.2}", r.rating))
+            .unwrap_or_else(|| "-".to_string());
+
+        message.push_str(&format!(
+            "\n[{}] {} rates, 1.0x etterna {}, pattern {}",
+            beatmap.difficulty,
+            beatmap.rates.len(),
+            etterna,
+            beatmap.main_pattern
+        ));
+    }
+
+    truncate(message)
+}
+
+fn truncate(mut message: String) -> String {
+    if message.len() > MAX_MESSAGE_LEN {
+        let mut end = MAX_MESSAGE_LEN - 3;
+        while !message.is_char_boundary(end) {
+            end -= 1;
+        }
+        message.truncate(end);
+        message.push_str("...");
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::mock::MockBackend;
+    use crate::core::query::search::SearchHit;
+
+    const HASH: &str = "0123456789abcdef0123456789abcdef";
+
+    fn commands(backend: &Arc<MockBackend>) -> BotCommands {
+        BotCommands {
+            backend: backend.clone(),
+        }
+    }
+
+    fn hit() -> SearchHit {
+        SearchHit {
+            beatmap_osu_id: Some(42),
+            beatmapset_osu_id: Some(7),
+            artist: "Artist".to_string(),
+            title: "Title".to_string(),
+            creator: "Mapper".to_string(),
+            difficulty: "4K Hard".to_string(),
+            status: "ranked".to_string(),
+            main_pattern: serde_json::json!([]),
+            osu_hash: HASH.to_string(),
+            centirate: 110,
+            bpm: 180.0,
+            drain_time: 120,
+            rating_type: "etterna".to_string(),
+            rating: 24.5,
+            stream: None,
+            jumpstream: None,
+            handstream: None,
+            stamina: None,
+            jackspeed: None,
+            chordjack: None,
+            technical: None,
+        }
+    }
+
+    #[test]
+    fn links_to_a_beatmap_are_parsed() {
+        for link in [
+            "https://osu.ppy.sh/beatmapsets/1234#mania/5678",
+            "https://osu.ppy.sh/beatmapsets/1234#mania/5678/",
+            "https://osu.ppy.sh/b/5678",
+            "https://osu.ppy.sh/b/5678?m=3",
+            "osu.ppy.sh/beatmaps/5678",
+            " https://osu.ppy.sh/beatmaps/5678/ ",
+        ] {
+            assert_eq!(beatmap_id_from_link(link), Some(5678), "{}", link);
+        }
+    }
+
+    #[test]
+    fn links_without_a_beatmap_are_rejected() {
+        for link in [
+            "https://osu.ppy.sh/beatmapsets/1234",
+            "https://osu.ppy.sh/beatmapsets/1234#mania",
+            "https://osu.ppy.sh/users/5678",
+            "5678",
+            "",
+        ] {
+            assert_eq!(beatmap_id_from_link(link), None, "{}", link);
+        }
+    }
+
+    #[tokio::test]
+    async fn rate_downloads_the_linked_beatmap() {
+        let backend = Arc::new(MockBackend::default());
+        let reply = commands(&backend)
+            .handle(BotCommand::Rate {
+                link: "https://osu.ppy.sh/beatmapsets/1234#mania/5678".to_string(),
+            })
+            .await;
+
+        assert!(reply.starts_with("Main pattern"), "{}", reply);
+        assert_eq!(*backend.downloaded.lock().unwrap(), [5678]);
+    }
+
+    #[tokio::test]
+    async fn recommend_filters_on_the_pattern_and_requested_skillsets() {
+        let backend = Arc::new(MockBackend {
+            hits: vec![hit()],
+            ..Default::default()
+        });
+        let reply = commands(&backend)
+            .handle(BotCommand::Recommend {
+                msd: 25.0,
+                pattern: "Jumpstream".to_string(),
+                skillsets: Some("Jumpstream, stamina".to_string()),
+            })
+            .await;
+
+        assert_eq!(
+            reply,
+            "Artist - Title [4K Hard] 1.1x: 24.50 <https://osu.ppy.sh/b/42>"
+        );
+        let requests = backend.requests.lock().unwrap();
+        let mut weights = SkillsetVector::splat(0.0);
+        weights.jumpstream = 1.0;
+        weights.stamina = 1.0;
+        assert_eq!(requests[0].target, Some(SkillsetVector::splat(25.0)));
+        assert_eq!(requests[0].pattern.as_deref(), Some("jumpstream"));
+        assert_eq!(requests[0].weights, weights);
+        assert_eq!(requests[0].rating_type, "etterna");
+    }
+
+    #[tokio::test]
+    async fn recommend_weights_every_skillset_by_default() {
+        let backend = Arc::new(MockBackend::default());
+        let reply = commands(&backend)
+            .handle(BotCommand::Recommend {
+                msd: 20.0,
+                pattern: "ln".to_string(),
+                skillsets: None,
+            })
+            .await;
+
+        assert_eq!(reply, "No processed LN map close to 20.0 MSD");
+        let requests = backend.requests.lock().unwrap();
+        assert_eq!(requests[0].pattern.as_deref(), Some("LN"));
+        assert_eq!(requests[0].weights, SkillsetVector::default());
+    }
+
+    #[tokio::test]
+    async fn recommend_rejects_bad_arguments() {
+        let backend = Arc::new(MockBackend::default());
+        let commands = commands(&backend);
+
+        let reply = commands
+            .handle(BotCommand::Recommend {
+                msd: 20.0,
+                pattern: "LN".to_string(),
+                skillsets: Some("stream,LN".to_string()),
+            })
+            .await;
+        assert!(reply.starts_with("Error: unknown skillset"), "{}", reply);
+
+        let reply = commands
+            .handle(BotCommand::Recommend {
+                msd: 20.0,
+                pattern: " ".to_string(),
+                skillsets: None,
+            })
+            .await;
+        assert_eq!(reply, "Error: expected a pattern");
+
+        let reply = commands
+            .handle(BotCommand::Recommend {
+                msd: f64::NAN,
+                pattern: "stream".to_string(),
+                skillsets: None,
+            })
+            .await;
+        assert!(reply.starts_with("Error"), "{}", reply);
+        assert!(backend.requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn queue_enqueues_unknown_hashes_only() {
+        let processed = "fedcba9876543210fedcba9876543210".to_string();
+        let backend = Arc::new(MockBackend {
+            processed: vec![processed.clone()],
+            ..Default::default()
+        });
+        let commands = commands(&backend);
+
+        let reply = commands
+            .handle(BotCommand::Queue {
+                hash: HASH.to_ascii_uppercase(),
+            })
+            .await;
+        assert_eq!(reply, format!("`{}` added to the queue", HASH));
+
+        let reply = commands
+            .handle(BotCommand::Queue {
+                hash: processed.clone(),
+            })
+            .await;
+        assert_eq!(reply, format!("`{}` is already processed", processed));
+
+        let reply = commands
+            .handle(BotCommand::Queue {
+                hash: "not-a-hash".to_string(),
+            })
+            .await;
+        assert!(reply.starts_with("Error"), "{}", reply);
+
+        assert_eq!(*backend.enqueued.lock().unwrap(), [HASH]);
+    }
+}
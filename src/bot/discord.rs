@@ -0,0 +1,284 @@
+use crate::bot::commands::{format_processed, BotCommand, BotCommands};
+use crate::core::notify::Notifier;
+use dto::models::beatmaps::full::types::Beatmapset;
+use serenity::all::{
+    ChannelId, Command, CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, EditInteractionResponse, EventHandler, GuildId, Http, Interaction, Ready,
+    ResolvedValue,
+};
+use serenity::async_trait;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+/// Traduit les interactions Discord en `BotCommand`
+pub struct DiscordHandler {
+    pub commands: Arc<BotCommands>,
+    /// Commandes enregistrées sur ce serveur uniquement (propagation immédiate)
+    pub guild_id: Option<GuildId>,
+}
+
+#[async_trait]
+impl EventHandler for DiscordHandler {
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        info!("Discord bot connected as {}", ready.user.name);
+
+        let result = match self.guild_id {
+            Some(guild_id) => {
+                guild_id
+                    .set_commands(&ctx.http, command_definitions())
+                    .await
+            }
+            None => Command::set_global_commands(&ctx.http, command_definitions()).await,
+        };
+        if let Err(e) = result {
+            error!("Failed to register Discord commands: {}", e);
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Command(interaction) = interaction else {
+            return;
+        };
+
+        let Some(command) = parse_command(&interaction) else {
+            warn!("Unknown Discord command: {}", interaction.data.name);
+            return;
+        };
+
+        // Le calcul peut dépasser les 3 secondes accordées pour répondre
+        if let Err(e) = interaction.defer(&ctx.http).await {
+            error!("Failed to defer Discord interaction: {}", e);
+            return;
+        }
+
+        let reply = self.commands.handle(command).await;
+        if let Err(e) = interaction
+            .edit_response(&ctx.http, EditInteractionResponse::new().content(reply))
+            .await
+        {
+            error!("Failed to answer Discord interaction: {}", e);
+        }
+    }
+}
+
+fn command_definitions() -> Vec<CreateCommand> {
+    vec![
+        CreateCommand::new("rate")
+            .description("Rate an osu!mania beatmap")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "link", "osu! beatmap link")
+                    .required(true),
+            ),
+        CreateCommand::new("recommend")
+            .description("Find processed maps closest to a difficulty")
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Number,
+                    "msd",
+                    "Target MSD of each skillset",
+                )
+                .required(true),
+            )
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "pattern",
+                    "Main pattern of the maps, e.g. jumpstream, chordjack or LN",
+                )
+                .required(true),
+            )
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::String,
+                "skillsets",
+                "Skillsets to match, e.g. jumpstream,stamina (all by default)",
+            )),
+        CreateCommand::new("queue")
+            .description("Add a beatmap to the processing queue")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "hash", "MD5 of the .osu file")
+                    .required(true),
+            ),
+    ]
+}
+
+fn parse_command(interaction: &CommandInteraction) -> Option<BotCommand> {
+    let options = interaction.data.options();
+    let string = |name: &str| {
+        options.iter().find_map(|option| match option.value {
+            ResolvedValue::String(value) if option.name == name => Some(value.to_string()),
+            _ => None,
+        })
+    };
+    let number = |name: &str| {
+        options.iter().find_map(|option| match option.value {
+            ResolvedValue::Number(value) if option.name == name => Some(value),
+            ResolvedValue::Integer(value) if option.name == name => Some(value as f64),
+            _ => None,
+        })
+    };
+
+    match interaction.data.name.as_str() {
+        "rate" => Some(BotCommand::Rate {
+            link: string("link")?,
+        }),
+        "recommend" => Some(BotCommand::Recommend {
+            msd: number("msd")?,
+            pattern: string("pattern")?,
+            skillsets: string("skillsets"),
+        }),
+        "queue" => Some(BotCommand::Queue {
+            hash: string("hash")?,
+        }),
+        _ => None,
+    }
+}
+
+/// Poste les résultats du worker dans un salon Discord
+pub struct DiscordNotifier {
+    pub http: Arc<Http>,
+    pub channel: ChannelId,
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    async fn beatmap_processed(&self, beatmapset: &Beatmapset) {
+        if let Err(e) = self
+            .channel
+            .say(&self.http, format_processed(beatmapset))
+            .await
+        {
+            warn!("Failed to post processing result on Discord: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::mock::MockBackend;
+    use serde_json::{json, Value};
+
+    /// Slash command interaction as the gateway sends it in `INTERACTION_CREATE`
+    fn interaction(name: &str, options: Value) -> CommandInteraction {
+        let payload = json!({
+            "id": "1100000000000000001",
+            "application_id": "1100000000000000002",
+            "type": 2,
+            "data": {
+                "id": "1100000000000000003",
+                "name": name,
+                "type": 1,
+                "options": options,
+            },
+            "channel_id": "1100000000000000004",
+            "token": "interaction-token",
+            "version": 1,
+            "locale": "en-US",
+            "entitlements": [],
+        });
+
+        match serde_json::from_value(payload).unwrap() {
+            Interaction::Command(interaction) => interaction,
+            other => panic!("not a command interaction: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn gateway_commands_are_parsed() {
+        let rate = interaction(
+            "rate",
+            json!([{ "name": "link", "type": 3, "value": "https://osu.ppy.sh/b/5678" }]),
+        );
+        assert_eq!(
+            parse_command(&rate),
+            Some(BotCommand::Rate {
+                link: "https://osu.ppy.sh/b/5678".to_string()
+            })
+        );
+
+        let recommend = interaction(
+            "recommend",
+            json!([
+                { "name": "msd", "type": 10, "value": 24.5 },
+                { "name": "pattern", "type": 3, "value": "LN" },
+            ]),
+        );
+        assert_eq!(
+            parse_command(&recommend),
+            Some(BotCommand::Recommend {
+                msd: 24.5,
+                pattern: "LN".to_string(),
+                skillsets: None,
+            })
+        );
+
+        let queue = interaction(
+            "queue",
+            json!([{ "name": "hash", "type": 3, "value": "0123456789abcdef0123456789abcdef" }]),
+        );
+        assert_eq!(
+            parse_command(&queue),
+            Some(BotCommand::Queue {
+                hash: "0123456789abcdef0123456789abcdef".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn integer_msd_is_accepted() {
+        let recommend = interaction(
+            "recommend",
+            json!([
+                { "name": "msd", "type": 4, "value": 20 },
+                { "name": "pattern", "type": 3, "value": "stream" },
+                { "name": "skillsets", "type": 3, "value": "stream,stamina" },
+            ]),
+        );
+        assert_eq!(
+            parse_command(&recommend),
+            Some(BotCommand::Recommend {
+                msd: 20.0,
+                pattern: "stream".to_string(),
+                skillsets: Some("stream,stamina".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_commands_and_missing_options_are_ignored() {
+        assert_eq!(parse_command(&interaction("ping", json!([]))), None);
+
+        let without_pattern = interaction(
+            "recommend",
+            json!([{ "name": "msd", "type": 10, "value": 24.5 }]),
+        );
+        assert_eq!(parse_command(&without_pattern), None);
+    }
+
+    #[tokio::test]
+    async fn gateway_interactions_are_answered_by_the_commands() {
+        let backend = Arc::new(MockBackend::default());
+        let handler = DiscordHandler {
+            commands: Arc::new(BotCommands {
+                backend: backend.clone(),
+            }),
+            guild_id: None,
+        };
+
+        let queue = interaction(
+            "queue",
+            json!([{ "name": "hash", "type": 3, "value": "0123456789ABCDEF0123456789ABCDEF" }]),
+        );
+        let command = parse_command(&queue).unwrap();
+        let reply = handler.commands.handle(command).await;
+
+        assert_eq!(
+            reply,
+            "`0123456789abcdef0123456789abcdef` added to the queue"
+        );
+        assert_eq!(
+            *backend.enqueued.lock().unwrap(),
+            ["0123456789abcdef0123456789abcdef"]
+        );
+    }
+}
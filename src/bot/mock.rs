@@ -0,0 +1,56 @@
+use crate::bot::backend::BotBackend;
+use crate::core::query::recommend::{RecommendRequest, Recommendation, Recommendations};
+use crate::core::query::search::SearchHit;
+use crate::core::rating::local::BeatmapRating;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+/// Backend answering from memory and recording what the commands asked for
+#[derive(Default)]
+pub struct MockBackend {
+    pub processed: Vec<String>,
+    pub hits: Vec<SearchHit>,
+    pub downloaded: Mutex<Vec<u32>>,
+    pub requests: Mutex<Vec<RecommendRequest>>,
+    pub enqueued: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl BotBackend for MockBackend {
+    async fn osu_file(&self, map_id: u32) -> Result<String> {
+        self.downloaded.lock().unwrap().push(map_id);
+        Ok(String::new())
+    }
+
+    async fn rate(&self, _osu_map: String) -> Result<BeatmapRating> {
+        Ok(BeatmapRating {
+            main_pattern: serde_json::json!(["jumpstream"]),
+            rates: Vec::new(),
+        })
+    }
+
+    async fn recommend(&self, request: &RecommendRequest) -> Result<Recommendations> {
+        self.requests.lock().unwrap().push(request.clone());
+        Ok(Recommendations {
+            target: request.target.unwrap(),
+            results: self
+                .hits
+                .iter()
+                .map(|hit| Recommendation {
+                    hit: hit.clone(),
+                    distance: 0.0,
+                })
+                .collect(),
+        })
+    }
+
+    async fn is_processed(&self, osu_hash: &str) -> Result<bool> {
+        Ok(self.processed.iter().any(|hash| hash == osu_hash))
+    }
+
+    async fn enqueue(&self, osu_hash: &str) -> Result<()> {
+        self.enqueued.lock().unwrap().push(osu_hash.to_string());
+        Ok(())
+    }
+}
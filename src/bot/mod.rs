@@ -0,0 +1,59 @@
+pub mod backend;
+pub mod commands;
+pub mod discord;
+#[cfg(test)]
+mod mock;
+
+use crate::core::notify::{NoopNotifier, Notifier};
+use crate::errors::ConfigError;
+use anyhow::Result;
+use commands::BotCommands;
+use discord::{DiscordHandler, DiscordNotifier};
+use serenity::all::{ChannelId, Client, GatewayIntents, GuildId};
+use std::sync::Arc;
+
+/// Options du bot Discord
+#[derive(Debug, Clone, Default)]
+pub struct BotOptions {
+    pub enabled: bool,
+    /// Requis uniquement si le bot est activé
+    pub token: Option<String>,
+    /// Serveur où enregistrer les commandes, globales sinon
+    pub guild_id: Option<u64>,
+    /// Salon où poster les beatmaps traitées
+    pub results_channel_id: Option<u64>,
+}
+
+/// Connecte le bot en tâche de fond et renvoie le notifier du salon de résultats
+pub async fn start_bot(options: &BotOptions, commands: BotCommands) -> Result<Arc<dyn Notifier>> {
+    let token = options.token.as_deref().ok_or(ConfigError::missing(
+        "DISCORD_BOT_TOKEN",
+        "the bot is enabled",
+    ))?;
+
+    let handler = DiscordHandler {
+        commands: Arc::new(commands),
+        guild_id: options.guild_id.map(GuildId::new),
+    };
+
+    // Les slash commands ne nécessitent aucun intent privilégié
+    let mut client = Client::builder(token, GatewayIntents::empty())
+        .event_handler(handler)
+        .await?;
+
+    let notifier: Arc<dyn Notifier> = match options.results_channel_id {
+        Some(channel_id) => Arc::new(DiscordNotifier {
+            http: client.http.clone(),
+            channel: ChannelId::new(channel_id),
+        }),
+        None => Arc::new(NoopNotifier),
+    };
+
+    tokio::spawn(async move {
+        if let Err(e) = client.start().await {
+            tracing::error!("Discord bot stopped: {}", e);
+        }
+    });
+
+    Ok(notifier)
+}
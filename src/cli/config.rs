@@ -0,0 +1,57 @@
+use crate::config::{Config, ConfigSource, Subsystem};
+use crate::errors::config::PRECEDENCE;
+use anyhow::{anyhow, Result};
+
+const SUBSYSTEMS: [Subsystem; 4] = [
+    Subsystem::Worker,
+    Subsystem::Api,
+    Subsystem::Bot,
+    Subsystem::Storage,
+];
+
+/// Affiche la configuration effective, secrets masqués, et la valide sans base de données
+pub(crate) fn check(source: &ConfigSource) -> Result<()> {
+    match source.file_path() {
+        Some(path) => println!("config file : {}", path.display()),
+        None => println!("config file : none"),
+    }
+    println!("precedence  : {}", PRECEDENCE);
+    println!();
+
+    for setting in source.effective_settings() {
+        match &setting.value {
+            Some(value) => println!("{} = {:?} ({})", setting.name, value, setting.origin),
+            None => println!("{} ({})", setting.name, setting.origin),
+        }
+    }
+
+    let config =
+        Config::from_source(source).map_err(|e| anyhow!("Invalid configuration: {}", e))?;
+    let redacted = config.redacted();
+    println!();
+    println!("{:#?}", redacted.rate);
+    println!("{:#?}", redacted.osu_api);
+    println!("{:#?}", redacted.worker);
+    println!("{:#?}", redacted.storage);
+    println!("{:#?}", redacted.discovery);
+    println!("{:#?}", redacted.refresh);
+    println!("{:#?}", redacted.server);
+    println!("{:#?}", redacted.bot);
+
+    println!();
+    let mut invalid = 0;
+    for subsystem in SUBSYSTEMS {
+        match config.validate(subsystem) {
+            Ok(()) => println!("{:?}: ok", subsystem),
+            Err(e) => {
+                invalid += 1;
+                println!("{:?}: {}", subsystem, e);
+            }
+        }
+    }
+
+    if invalid > 0 {
+        return Err(anyhow!("{} subsystem(s) not correctly configured", invalid));
+    }
+    Ok(())
+}
@@ -0,0 +1,244 @@
+mod config;
+mod rate;
+mod recalc;
+mod status;
+mod worker;
+
+use crate::api::fixture::FixtureOsuApi;
+use crate::api::osu::OsuApiService;
+use crate::api::OsuApi;
+use crate::config::{Config, ConfigSource, Subsystem};
+use crate::core;
+use crate::core::notify::NoopNotifier;
+use crate::core::shutdown::Shutdown;
+use crate::errors::ConfigError;
+use crate::utils::rate::file_manager::FileManager;
+use anyhow::{anyhow, Result};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Parser)]
+#[command(name = "pendora", version, about = "osu!mania beatmap rating pipeline")]
+pub struct Cli {
+    /// Defaults to `worker` when omitted
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// TOML config file (PENDORA_CONFIG, then ./pendora.toml if present)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// Override a setting by its environment variable name, e.g. `--set RATE_RATES=1.0,1.1`
+    #[arg(long = "set", global = true, value_name = "NAME=VALUE")]
+    pub overrides: Vec<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the queue worker with the discovery and refresh tasks
+    Worker,
+    /// Print the ratings of a local .osu file, without database
+    Rate(RateArgs),
+    /// Add beatmaps to the pending queue
+    Enqueue(EnqueueArgs),
+    /// Recompute the rates of already processed beatmaps
+    Recalc(RecalcArgs),
+    /// Import .osu/.osz files from a local folder
+    Import(ImportArgs),
+    /// Delete orphan rate files and deduplicate identical ones
+    Gc(GcArgs),
+    /// Show queue depth and failures
+    Status,
+    /// Serve the HTTP rating API
+    Serve,
+    /// Inspect the layered configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Print the effective configuration with secrets redacted and validate it
+    Check,
+}
+
+#[derive(Debug, Args)]
+pub struct RateArgs {
+    /// Path to the .osu file
+    pub file: PathBuf,
+    /// Rates to compute, e.g. `--rates 1.0,1.2` (RATE_RATES by default)
+    #[arg(long, value_delimiter = ',')]
+    pub rates: Option<Vec<String>>,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("target").required(true).args(["hash", "beatmap", "beatmapset"])))]
+pub struct EnqueueArgs {
+    /// MD5 hash of the .osu file
+    pub hash: Option<String>,
+    /// osu! beatmap id
+    #[arg(long)]
+    pub beatmap: Option<i32>,
+    /// osu! beatmapset id, enqueues every allowed difficulty
+    #[arg(long)]
+    pub beatmapset: Option<u32>,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("target").required(true).args(["beatmap", "all"])))]
+pub struct RecalcArgs {
+    /// osu! beatmap ids to recompute
+    #[arg(long, value_delimiter = ',')]
+    pub beatmap: Vec<i32>,
+    /// Recompute every processed beatmap
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// A .osu/.osz file or a folder such as osu!'s Songs folder
+    pub path: PathBuf,
+    /// Process right away instead of enqueuing
+    #[arg(long, conflicts_with = "offline")]
+    pub process: bool,
+    /// Process right away from the files only, without the osu! API
+    #[arg(long)]
+    pub offline: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct GcArgs {
+    /// Only report what would be deleted or linked
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Keep unreferenced files younger than this, they may belong to a beatmap being processed
+    #[arg(long, default_value_t = 3600)]
+    pub min_age_secs: u64,
+}
+
+pub async fn run(cli: Cli) -> Result<()> {
+    let command = cli.command.unwrap_or(Command::Worker);
+    let source = ConfigSource::new(cli.config.as_deref(), &cli.overrides)
+        .map_err(|e| anyhow!("Error while loading config: {}", e))?;
+
+    // Ni le rating local ni la vérification de config ne dépendent de la base ou de l'API
+    match &command {
+        Command::Rate(args) => return rate::run(args, &source),
+        Command::Config(ConfigCommand::Check) => return config::check(&source),
+        _ => {}
+    }
+
+    let mut config =
+        Config::from_source(&source).map_err(|e| anyhow!("Error while loading config: {}", e))?;
+    for subsystem in command.subsystems() {
+        config
+            .validate(*subsystem)
+            .map_err(|e| anyhow!("Invalid configuration: {}", e))?;
+    }
+    FileManager::init(&config.storage);
+
+    // Phase de démarrage : la base peut arriver après le worker
+    core::startup::connect_database(&mut config.database, &config.database_options).await?;
+
+    match command {
+        Command::Worker => worker::run(config).await,
+        Command::Rate(_) | Command::Config(_) => unreachable!(),
+        Command::Enqueue(args) => enqueue(config, args).await,
+        Command::Recalc(args) => recalc::run(config, args).await,
+        Command::Import(args) => import(config, args).await,
+        Command::Gc(args) => {
+            core::gc::run_gc(
+                &config.database,
+                args.dry_run,
+                Duration::from_secs(args.min_age_secs),
+            )
+            .await?;
+            Ok(())
+        }
+        Command::Status => status::run(&config).await,
+        Command::Serve => {
+            let osu_api = build_osu_api(&config).await?;
+            crate::server::serve(config, osu_api, Shutdown::listen()).await
+        }
+    }
+}
+
+impl Command {
+    /// Sous-systèmes dont la configuration doit être valide avant de lancer la commande
+    fn subsystems(&self) -> &'static [Subsystem] {
+        match self {
+            Command::Worker => &[Subsystem::Worker, Subsystem::Bot, Subsystem::Storage],
+            Command::Rate(_) | Command::Config(_) | Command::Status | Command::Gc(_) => &[],
+            Command::Enqueue(_) => &[Subsystem::Worker],
+            Command::Recalc(_) => &[Subsystem::Storage],
+            Command::Import(args) if args.offline => &[Subsystem::Storage],
+            Command::Import(_) => &[Subsystem::Worker, Subsystem::Storage],
+            Command::Serve => &[Subsystem::Api],
+        }
+    }
+}
+
+pub(crate) async fn build_osu_api(config: &Config) -> Result<Arc<dyn OsuApi>> {
+    Ok(match &config.osu_api.fixtures_dir {
+        Some(dir) => {
+            tracing::info!("Using osu! API fixtures from {}", dir.display());
+            Arc::new(FixtureOsuApi::new(dir.clone()))
+        }
+        None => {
+            let credentials = config
+                .osu_api
+                .credentials
+                .as_ref()
+                .ok_or(ConfigError::missing(
+                    "OSU_CLIENT_ID",
+                    "required to call the osu! API without OSU_API_FIXTURES_DIR",
+                ))?;
+            Arc::new(OsuApiService::new(credentials, config.osu_api.clone()).await?)
+        }
+    })
+}
+
+async fn enqueue(config: Config, args: EnqueueArgs) -> Result<()> {
+    let osu_api = build_osu_api(&config).await?;
+
+    let count = if let Some(id) = args.beatmap {
+        core::enqueue::enqueue_beatmap_id(&config.database, osu_api.as_ref(), id).await?
+    } else if let Some(id) = args.beatmapset {
+        core::enqueue::enqueue_beatmapset_id(&config.database, osu_api.as_ref(), id).await?
+    } else if let Some(hash) = &args.hash {
+        core::enqueue::enqueue_hash(&config.database, hash).await?;
+        1
+    } else {
+        0
+    };
+
+    tracing::info!("{} beatmap(s) enqueued", count);
+    Ok(())
+}
+
+async fn import(config: Config, args: ImportArgs) -> Result<()> {
+    let mode = if args.offline {
+        core::import::ImportMode::Offline
+    } else if args.process {
+        core::import::ImportMode::Process
+    } else {
+        core::import::ImportMode::Enqueue
+    };
+
+    let worker = core::worker::BeatmapWorker {
+        osu_api_service: build_osu_api(&config).await?,
+        notifier: Arc::new(NoopNotifier),
+        config,
+    };
+    core::import::import_path(&worker, &args.path, mode).await?;
+    Ok(())
+}
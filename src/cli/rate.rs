@@ -0,0 +1,64 @@
+use crate::cli::{OutputFormat, RateArgs};
+use crate::config::{Config, ConfigSource};
+use crate::core::rating::local::{rate_osu_file, BeatmapRating};
+use anyhow::{anyhow, Result};
+use minacalc_rs::Calc;
+
+/// Calcule et affiche les `Rates` d'un `.osu` local, sans base de données
+pub(crate) fn run(args: &RateArgs, source: &ConfigSource) -> Result<()> {
+    let osu_map = std::fs::read_to_string(&args.file)?;
+
+    let mut options = Config::load_rate_options(source)
+        .map_err(|e| anyhow!("Error while loading config: {}", e))?;
+    if let Some(rates) = &args.rates {
+        options.centirates = rates
+            .iter()
+            .map(|rate| {
+                crate::config::parse_centirate(rate)
+                    .ok_or_else(|| anyhow!("invalid rate {}, expected 0.7 to 2.0 by 0.1", rate))
+            })
+            .collect::<Result<_>>()?;
+    }
+
+    let calc = Calc::new().map_err(|e| anyhow!("{:?}", e))?;
+    let rating = rate_osu_file(&osu_map, &calc, &options)?;
+
+    match args.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&rating)?),
+        OutputFormat::Table => print_table(&rating),
+    }
+
+    Ok(())
+}
+
+fn print_table(rating: &BeatmapRating) {
+    println!("main pattern: {}", rating.main_pattern);
+    println!(
+        "{:>5} {:>7} {:>5} {:>5} {:>6} {:>9} {:>9} {:>9}",
+        "rate", "bpm", "od", "hp", "drain", "etterna", "sunnyxxy", "osu"
+    );
+
+    for rated in &rating.rates {
+        let rates = &rated.rates;
+        let value = |rating_type: &str| {
+            rates
+                .rating
+                .iter()
+                .find(|r| r.rating_type == rating_type)
+                .map(|r| format!("{:.2}", r.rating))
+                .unwrap_or_else(|| "-".to_string())
+        };
+
+        println!(
+            "{:>5.1} {:>7.1} {:>5.1} {:>5.1} {:>6} {:>9} {:>9} {:>9}",
+            rates.centirate as f64 / 100.0,
+            rates.bpm,
+            rated.difficulty.od,
+            rated.difficulty.hp,
+            rates.drain_time,
+            value("etterna"),
+            value("sunnyxxy"),
+            value("osu")
+        );
+    }
+}
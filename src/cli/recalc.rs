@@ -0,0 +1,46 @@
+use crate::cli::{build_osu_api, RecalcArgs};
+use crate::config::Config;
+use crate::core::notify::NoopNotifier;
+use crate::core::worker::BeatmapWorker;
+use crate::store;
+use crate::store::beatmap::ProcessedBeatmap;
+use anyhow::Result;
+use db::models::beatmaps::beatmap::BeatmapRow;
+use std::sync::Arc;
+
+pub(crate) async fn run(config: Config, args: RecalcArgs) -> Result<()> {
+    let pool = config.database.get_pool();
+
+    let beatmaps = if args.all {
+        store::beatmap::find_all(pool).await?
+    } else {
+        let mut beatmaps: Vec<ProcessedBeatmap> = Vec::new();
+        for osu_id in &args.beatmap {
+            match BeatmapRow::find_by_osu_id(pool, *osu_id).await? {
+                Some(beatmap) => beatmaps.push(beatmap.into()),
+                None => tracing::warn!("Beatmap {} has not been processed, skipping", osu_id),
+            }
+        }
+        beatmaps
+    };
+
+    let worker = BeatmapWorker {
+        osu_api_service: build_osu_api(&config).await?,
+        notifier: Arc::new(NoopNotifier),
+        config: config.clone(),
+    };
+    let mut failed = 0;
+    for beatmap in &beatmaps {
+        if let Err(e) = worker.recalc_beatmap(beatmap).await {
+            tracing::error!("Recalc failed for beatmap {}: {}", beatmap.id, e);
+            failed += 1;
+        }
+    }
+
+    tracing::info!(
+        "Recalc done: {} beatmaps, {} failed",
+        beatmaps.len(),
+        failed
+    );
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use crate::config::Config;
+use crate::core::status::queue_status;
+use anyhow::Result;
+
+pub(crate) async fn run(config: &Config) -> Result<()> {
+    let status = queue_status(&config.database).await?;
+
+    println!("pending beatmaps : {}", status.pending);
+    println!("failed queries   : {}", status.failed);
+    println!("processed maps   : {}", status.beatmaps);
+    println!("stored rates     : {}", status.rates);
+    Ok(())
+}
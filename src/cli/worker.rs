@@ -0,0 +1,71 @@
+use crate::bot::backend::LibraryBackend;
+use crate::bot::commands::BotCommands;
+use crate::bot::start_bot;
+use crate::cli::build_osu_api;
+use crate::config::Config;
+use crate::core;
+use crate::core::notify::{NoopNotifier, Notifier};
+use crate::core::shutdown::Shutdown;
+use crate::server::calculation_slots;
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Comportement par défaut : worker de la file d'attente, discovery et refresh
+pub(crate) async fn run(config: Config) -> Result<()> {
+    let osu_api_service = build_osu_api(&config).await?;
+    let shutdown = Shutdown::listen();
+    let mut tasks = Vec::new();
+
+    if config.discovery.enabled {
+        let discovery = core::discovery::DiscoveryTask {
+            database: config.database.clone(),
+            osu_api: osu_api_service.clone(),
+            options: config.discovery.clone(),
+        };
+        let shutdown = shutdown.clone();
+        tasks.push(tokio::spawn(async move { discovery.run(shutdown).await }));
+    }
+
+    if config.refresh.enabled {
+        let refresh = core::refresh::StatusRefreshTask {
+            database: config.database.clone(),
+            osu_api: osu_api_service.clone(),
+            options: config.refresh.clone(),
+        };
+        let shutdown = shutdown.clone();
+        tasks.push(tokio::spawn(async move { refresh.run(shutdown).await }));
+    }
+
+    let notifier: Arc<dyn Notifier> = if config.bot.enabled {
+        let commands = BotCommands {
+            backend: Arc::new(LibraryBackend {
+                database: config.database.clone(),
+                osu_api: osu_api_service.clone(),
+                rate: config.rate.clone(),
+                calculations: calculation_slots(&config.server),
+            }),
+        };
+        start_bot(&config.bot, commands).await?
+    } else {
+        Arc::new(NoopNotifier)
+    };
+
+    tracing::info!("Application started successfully");
+
+    let beatmap_worker = core::worker::BeatmapWorker {
+        config,
+        osu_api_service,
+        notifier,
+    };
+    // Discovery et refresh s'arrêtent sur le même signal que le worker
+    let result = beatmap_worker.start(shutdown).await;
+    for task in tasks {
+        if let Err(e) = task.await {
+            tracing::error!("Background task failed: {}", e);
+        }
+    }
+    result?;
+
+    tracing::info!("Application stopped");
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use crate::bot::BotOptions;
+use crate::config::env::optional;
+use crate::config::file::ConfigFile;
+
+/// Charge les options du bot Discord depuis la configuration
+///
+/// Le jeton n'est exigé que par `Config::validate(Subsystem::Bot)`
+pub(crate) fn load_bot_options(settings: &ConfigFile) -> BotOptions {
+    let default = BotOptions::default();
+    let discord = &settings.discord;
+
+    BotOptions {
+        enabled: discord.enabled.unwrap_or(default.enabled),
+        token: optional(&discord.bot_token, default.token),
+        guild_id: discord.guild_id.or(default.guild_id),
+        results_channel_id: discord.results_channel_id.or(default.results_channel_id),
+    }
+}
@@ -0,0 +1,23 @@
+use crate::config::env::optional;
+use crate::config::file::ConfigFile;
+use crate::core::startup::DatabaseOptions;
+use std::time::Duration;
+
+/// Charge les options de connexion à la base depuis la configuration, sans se connecter
+pub(crate) fn load_database_options(settings: &ConfigFile) -> DatabaseOptions {
+    let default = DatabaseOptions::default();
+    let database = &settings.database;
+
+    DatabaseOptions {
+        url: optional(&database.url, default.url),
+        connect_max_attempts: database
+            .connect_max_attempts
+            .unwrap_or(default.connect_max_attempts),
+        connect_initial_backoff: database
+            .connect_backoff_ms
+            .map_or(default.connect_initial_backoff, Duration::from_millis),
+        connect_max_backoff: database
+            .connect_max_backoff_secs
+            .map_or(default.connect_max_backoff, Duration::from_secs),
+    }
+}
@@ -1,13 +1,28 @@
+use crate::api::osu::OsuApiOptions;
+use crate::bot::BotOptions;
 use crate::config::Config;
+use crate::core::discovery::DiscoveryOptions;
+use crate::core::refresh::RefreshOptions;
+use crate::core::startup::DatabaseOptions;
+use crate::core::worker::WorkerOptions;
+use crate::server::ServerOptions;
+use crate::utils::rate::file_manager::StorageOptions;
+use crate::utils::rate::options::RateOptions;
 use db::db::DatabaseManager;
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             database: DatabaseManager::new(),
-            osu_client_id: "".to_string(),
-            osu_client_secret: "".to_string(),
-            discord_bot_token: "".to_string(),
+            database_options: DatabaseOptions::default(),
+            rate: RateOptions::default(),
+            osu_api: OsuApiOptions::default(),
+            discovery: DiscoveryOptions::default(),
+            refresh: RefreshOptions::default(),
+            server: ServerOptions::default(),
+            bot: BotOptions::default(),
+            worker: WorkerOptions::default(),
+            storage: StorageOptions::default(),
         }
     }
 }
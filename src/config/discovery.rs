@@ -0,0 +1,39 @@
+use crate::config::env::or_default;
+use crate::config::file::ConfigFile;
+use crate::core::discovery::DiscoveryOptions;
+use crate::errors::config::ConfigError;
+use crate::utils::rank_status_from_string;
+use std::time::Duration;
+
+/// Charge les options de découverte automatique depuis la configuration
+pub(crate) fn load_discovery_options(
+    settings: &ConfigFile,
+) -> Result<DiscoveryOptions, ConfigError> {
+    let default = DiscoveryOptions::default();
+    let discovery = &settings.discovery;
+
+    let statuses = match &discovery.statuses {
+        Some(statuses) => statuses
+            .iter()
+            .map(|s| {
+                rank_status_from_string(s.trim()).ok_or_else(|| {
+                    ConfigError::invalid(
+                        "DISCOVERY_STATUSES",
+                        format!("unknown rank status {:?}", s),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        None => default.statuses,
+    };
+
+    Ok(DiscoveryOptions {
+        enabled: discovery.enabled.unwrap_or(default.enabled),
+        interval: discovery
+            .interval_secs
+            .map_or(default.interval, Duration::from_secs),
+        max_pages: discovery.max_pages.unwrap_or(default.max_pages),
+        cursor_path: or_default(&discovery.cursor_path, default.cursor_path),
+        statuses,
+    })
+}
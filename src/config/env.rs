@@ -0,0 +1,54 @@
+use crate::errors::config::ConfigError;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Valeur dont la forme vide (`""`) désactive un réglage, comme une variable vide
+pub(crate) trait Blank: Clone {
+    fn is_blank(&self) -> bool;
+}
+
+impl Blank for String {
+    fn is_blank(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl Blank for PathBuf {
+    fn is_blank(&self) -> bool {
+        self.as_os_str().is_empty()
+    }
+}
+
+/// Lit une valeur optionnelle : absente -> valeur par défaut, vide -> None
+pub(crate) fn optional<T: Blank>(value: &Option<T>, default: Option<T>) -> Option<T> {
+    match value {
+        Some(value) if value.is_blank() => None,
+        Some(value) => Some(value.clone()),
+        None => default,
+    }
+}
+
+/// Lit une valeur, avec une valeur par défaut si absente ou vide
+pub(crate) fn or_default<T: Blank>(value: &Option<T>, default: T) -> T {
+    optional(value, None).unwrap_or(default)
+}
+
+/// Parse un texte (ex: adresse, codec), avec une valeur par défaut si absent ou vide
+pub(crate) fn parsed_or<T: FromStr>(
+    name: &str,
+    value: &Option<String>,
+    default: T,
+) -> Result<T, ConfigError> {
+    match optional(value, None) {
+        Some(value) => value
+            .parse::<T>()
+            .map_err(|_| ConfigError::parse(name, value, type_label::<T>())),
+        None => Ok(default),
+    }
+}
+
+/// Nom court du type attendu pour les messages d'erreur (ex: `u32`, `SocketAddr`)
+pub(crate) fn type_label<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
@@ -0,0 +1,319 @@
+use crate::config::env::type_label;
+use crate::errors::config::ConfigError;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Fichier TOML de configuration, une table par sous-système
+///
+/// Chaque clé correspond à une variable d'environnement (ex: `[worker] poll_interval_secs`
+/// -> `WORKER_POLL_INTERVAL_SECS`), appliquée par-dessus avec `ConfigFile::set` ; une
+/// chaîne vide désactive la valeur comme en env. Un champ absent garde la valeur par défaut.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    pub database: DatabaseSection,
+    pub osu: OsuSection,
+    pub rate: RateSection,
+    pub calculators: CalculatorsSection,
+    pub worker: WorkerSection,
+    pub storage: StorageSection,
+    pub discovery: DiscoverySection,
+    pub refresh: RefreshSection,
+    pub server: ServerSection,
+    pub discord: DiscordSection,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSection {
+    pub url: Option<String>,
+    pub connect_max_attempts: Option<u32>,
+    pub connect_backoff_ms: Option<u64>,
+    pub connect_max_backoff_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OsuSection {
+    pub client_id: Option<u64>,
+    pub client_secret: Option<String>,
+    pub requests_per_minute: Option<u32>,
+    pub max_retries: Option<u32>,
+    pub retry_base_delay_ms: Option<u64>,
+    pub max_retry_delay_secs: Option<u64>,
+    pub download_timeout_secs: Option<u64>,
+    pub fixtures_dir: Option<PathBuf>,
+    pub cache_dir: Option<PathBuf>,
+    pub cache_final_ttl_secs: Option<u64>,
+    pub cache_short_ttl_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateSection {
+    pub rates: Option<Vec<f64>>,
+    /// `Some(None)` quand une variable vide désactive la réécriture
+    pub beatmap_id: Option<Option<i32>>,
+    pub beatmapset_id: Option<Option<i32>>,
+    pub tag: Option<String>,
+    pub creator_suffix: Option<String>,
+    /// `keep`, `constant` ou une valeur
+    pub od: Option<Scalar>,
+    /// `keep` ou une valeur
+    pub hp: Option<Scalar>,
+    pub compression_codec: Option<String>,
+    pub compression_level: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalculatorsSection {
+    pub osu: Option<bool>,
+    pub sunnyxxy: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkerSection {
+    pub poll_interval_secs: Option<u64>,
+    pub error_backoff_secs: Option<u64>,
+    pub shutdown_timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageSection {
+    pub beatmap_root: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiscoverySection {
+    pub enabled: Option<bool>,
+    pub interval_secs: Option<u64>,
+    pub max_pages: Option<u32>,
+    pub statuses: Option<Vec<String>>,
+    pub cursor_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefreshSection {
+    pub enabled: Option<bool>,
+    pub interval_secs: Option<u64>,
+    pub statuses: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSection {
+    pub bind: Option<String>,
+    pub max_body_bytes: Option<usize>,
+    pub requests_per_minute: Option<u32>,
+    pub max_concurrent_calculations: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiscordSection {
+    pub enabled: Option<bool>,
+    pub bot_token: Option<String>,
+    pub guild_id: Option<u64>,
+    pub results_channel_id: Option<u64>,
+}
+
+/// Valeur acceptant un mot-clé ou un nombre (ex: `od = "keep"` ou `od = 8.0`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Scalar {
+    Number(f64),
+    Text(String),
+}
+
+/// Lit et valide un fichier TOML : les sections typées, et le document brut pour
+/// afficher les valeurs telles qu'écrites
+pub(crate) fn read_config_file(path: &Path) -> Result<(ConfigFile, toml::Table), ConfigError> {
+    let content = std::fs::read_to_string(path).map_err(|e| ConfigError::file(path, e))?;
+    let file = toml::from_str(&content).map_err(|e| ConfigError::file(path, e))?;
+    let document = toml::from_str(&content).map_err(|e| ConfigError::file(path, e))?;
+    Ok((file, document))
+}
+
+impl ConfigFile {
+    /// Remplace le champ d'un réglage par une valeur de l'environnement ou de `--set`
+    ///
+    /// Une valeur vide rétablit la valeur par défaut, ou désactive un réglage optionnel.
+    pub(crate) fn set(&mut self, name: &str, value: &str) -> Result<(), ConfigError> {
+        match name {
+            "DATABASE_URL" => self.database.url = text(value),
+            "DATABASE_CONNECT_MAX_ATTEMPTS" => {
+                self.database.connect_max_attempts = parse(name, value)?
+            }
+            "DATABASE_CONNECT_BACKOFF_MS" => self.database.connect_backoff_ms = parse(name, value)?,
+            "DATABASE_CONNECT_MAX_BACKOFF_SECS" => {
+                self.database.connect_max_backoff_secs = parse(name, value)?
+            }
+
+            "OSU_CLIENT_ID" => self.osu.client_id = parse(name, value)?,
+            "OSU_CLIENT_SECRET" => self.osu.client_secret = text(value),
+            "OSU_API_REQUESTS_PER_MINUTE" => self.osu.requests_per_minute = parse(name, value)?,
+            "OSU_API_MAX_RETRIES" => self.osu.max_retries = parse(name, value)?,
+            "OSU_API_RETRY_BASE_DELAY_MS" => self.osu.retry_base_delay_ms = parse(name, value)?,
+            "OSU_API_MAX_RETRY_DELAY_SECS" => self.osu.max_retry_delay_secs = parse(name, value)?,
+            "OSU_API_DOWNLOAD_TIMEOUT_SECS" => self.osu.download_timeout_secs = parse(name, value)?,
+            "OSU_API_FIXTURES_DIR" => self.osu.fixtures_dir = path(value),
+            "OSU_API_CACHE_DIR" => self.osu.cache_dir = path(value),
+            "OSU_API_CACHE_FINAL_TTL_SECS" => self.osu.cache_final_ttl_secs = parse(name, value)?,
+            "OSU_API_CACHE_SHORT_TTL_SECS" => self.osu.cache_short_ttl_secs = parse(name, value)?,
+
+            "RATE_RATES" => self.rate.rates = list(name, value)?,
+            "RATE_BEATMAP_ID" => self.rate.beatmap_id = Some(parse(name, value)?),
+            "RATE_BEATMAPSET_ID" => self.rate.beatmapset_id = Some(parse(name, value)?),
+            "RATE_TAG" => self.rate.tag = text(value),
+            "RATE_CREATOR_SUFFIX" => self.rate.creator_suffix = text(value),
+            "RATE_OD" => self.rate.od = scalar(value),
+            "RATE_HP" => self.rate.hp = scalar(value),
+            "RATE_COMPRESSION_CODEC" => self.rate.compression_codec = text(value),
+            "RATE_COMPRESSION_LEVEL" => self.rate.compression_level = parse(name, value)?,
+            "CALCULATOR_OSU_ENABLED" => self.calculators.osu = parse(name, value)?,
+            "CALCULATOR_SUNNYXXY_ENABLED" => self.calculators.sunnyxxy = parse(name, value)?,
+
+            "WORKER_POLL_INTERVAL_SECS" => self.worker.poll_interval_secs = parse(name, value)?,
+            "WORKER_ERROR_BACKOFF_SECS" => self.worker.error_backoff_secs = parse(name, value)?,
+            "WORKER_SHUTDOWN_TIMEOUT_SECS" => {
+                self.worker.shutdown_timeout_secs = parse(name, value)?
+            }
+            "STORAGE_BEATMAP_ROOT" => self.storage.beatmap_root = path(value),
+
+            "DISCOVERY_ENABLED" => self.discovery.enabled = parse(name, value)?,
+            "DISCOVERY_INTERVAL_SECS" => self.discovery.interval_secs = parse(name, value)?,
+            "DISCOVERY_MAX_PAGES" => self.discovery.max_pages = parse(name, value)?,
+            "DISCOVERY_STATUSES" => self.discovery.statuses = list(name, value)?,
+            "DISCOVERY_CURSOR_PATH" => self.discovery.cursor_path = path(value),
+            "REFRESH_ENABLED" => self.refresh.enabled = parse(name, value)?,
+            "REFRESH_INTERVAL_SECS" => self.refresh.interval_secs = parse(name, value)?,
+            "REFRESH_STATUSES" => self.refresh.statuses = list(name, value)?,
+
+            "SERVER_BIND" => self.server.bind = text(value),
+            "SERVER_MAX_BODY_BYTES" => self.server.max_body_bytes = parse(name, value)?,
+            "SERVER_REQUESTS_PER_MINUTE" => self.server.requests_per_minute = parse(name, value)?,
+            "SERVER_MAX_CONCURRENT_CALCULATIONS" => {
+                self.server.max_concurrent_calculations = parse(name, value)?
+            }
+
+            "DISCORD_ENABLED" => self.discord.enabled = parse(name, value)?,
+            "DISCORD_BOT_TOKEN" => self.discord.bot_token = text(value),
+            "DISCORD_GUILD_ID" => self.discord.guild_id = parse(name, value)?,
+            "DISCORD_RESULTS_CHANNEL_ID" => self.discord.results_channel_id = parse(name, value)?,
+
+            _ => return Err(ConfigError::invalid(name, "unknown setting")),
+        }
+        Ok(())
+    }
+}
+
+/// Texte gardé tel quel, vide compris : chaque chargeur décide de son sens
+fn text(value: &str) -> Option<String> {
+    Some(value.to_string())
+}
+
+fn path(value: &str) -> Option<PathBuf> {
+    Some(PathBuf::from(value))
+}
+
+/// Valeur vide -> None, sinon parsée dans le type du champ
+fn parse<T: FromStr>(name: &str, value: &str) -> Result<Option<T>, ConfigError> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| ConfigError::parse(name, value, type_label::<T>()))
+}
+
+/// Liste séparée par des virgules (ex: `1.0,1.1,1.2`)
+fn list<T: FromStr>(name: &str, value: &str) -> Result<Option<Vec<T>>, ConfigError> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .split(',')
+        .map(|item| item.trim().parse())
+        .collect::<Result<_, _>>()
+        .map(Some)
+        .map_err(|_| {
+            let expected = format!("comma-separated {}", type_label::<T>());
+            ConfigError::parse(name, value, &expected)
+        })
+}
+
+/// Nombre si la valeur en est un, mot-clé sinon, comme dans le fichier
+fn scalar(value: &str) -> Option<Scalar> {
+    if value.is_empty() {
+        return None;
+    }
+    Some(match value.parse() {
+        Ok(number) => Scalar::Number(number),
+        Err(_) => Scalar::Text(value.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_values_are_typed_by_serde() {
+        let file: ConfigFile = toml::from_str(
+            "[worker]\npoll_interval_secs = 5\n[rate]\nrates = [1.0, 1.2]\nod = \"constant\"\n",
+        )
+        .unwrap();
+        assert_eq!(file.worker.poll_interval_secs, Some(5));
+        assert_eq!(file.rate.rates, Some(vec![1.0, 1.2]));
+        assert!(matches!(file.rate.od, Some(Scalar::Text(ref text)) if text == "constant"));
+
+        let error = toml::from_str::<ConfigFile>("[worker]\npoll_interval_secs = \"5\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("invalid type"), "{}", error);
+        assert!(toml::from_str::<ConfigFile>("[worker]\npoll_secs = 5\n").is_err());
+    }
+
+    #[test]
+    fn set_overrides_the_typed_field() {
+        let mut file: ConfigFile =
+            toml::from_str("[worker]\npoll_interval_secs = 5\n[rate]\nbeatmap_id = 7\n").unwrap();
+
+        file.set("WORKER_POLL_INTERVAL_SECS", "12").unwrap();
+        file.set("RATE_RATES", "1.0, 1.5").unwrap();
+        file.set("RATE_HP", "8.5").unwrap();
+        assert_eq!(file.worker.poll_interval_secs, Some(12));
+        assert_eq!(file.rate.rates, Some(vec![1.0, 1.5]));
+        assert!(matches!(file.rate.hp, Some(Scalar::Number(hp)) if hp == 8.5));
+
+        // Vide : retour au défaut, ou désactivation des réglages optionnels
+        file.set("WORKER_POLL_INTERVAL_SECS", "").unwrap();
+        file.set("RATE_BEATMAP_ID", "").unwrap();
+        assert_eq!(file.worker.poll_interval_secs, None);
+        assert_eq!(file.rate.beatmap_id, Some(None));
+    }
+
+    #[test]
+    fn set_rejects_values_of_the_wrong_type() {
+        let mut file = ConfigFile::default();
+        for (name, value) in [
+            ("WORKER_POLL_INTERVAL_SECS", "-1"),
+            ("DISCORD_ENABLED", "yes"),
+            ("RATE_RATES", "1.0,fast"),
+        ] {
+            assert!(
+                matches!(file.set(name, value), Err(ConfigError::ParseError { .. })),
+                "{}={}",
+                name,
+                value
+            );
+        }
+    }
+}
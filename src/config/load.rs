@@ -1,56 +1,50 @@
+use crate::config::bot::load_bot_options;
+use crate::config::database::load_database_options;
+use crate::config::discovery::load_discovery_options;
+use crate::config::osu_api::load_osu_api_options;
+use crate::config::rate::load_rate_options;
+use crate::config::refresh::load_refresh_options;
+use crate::config::server::load_server_options;
+use crate::config::source::ConfigSource;
+use crate::config::storage::load_storage_options;
+use crate::config::worker::load_worker_options;
 use crate::config::Config;
 use crate::errors::config::ConfigError;
-use db::config::DatabaseConfig;
+use crate::utils::rate::options::RateOptions;
 use db::db::DatabaseManager;
-use dotenvy::dotenv;
-use std::env;
 
 impl Config {
-    /// Charge la configuration depuis les variables d'environnement
-    pub async fn load() -> Result<Self, ConfigError> {
-        // Charger le fichier .env s'il existe
-        dotenv().ok();
-
-        let osu_client_id = env::var("OSU_CLIENT_ID")
-            .map_err(|_| ConfigError::MissingVariable("OSU_CLIENT_ID".to_string()))?;
-
-        let osu_client_secret = env::var("OSU_CLIENT_SECRET")
-            .map_err(|_| ConfigError::MissingVariable("OSU_CLIENT_SECRET".to_string()))?;
+    /// Charge la configuration depuis l'environnement et `pendora.toml`
+    ///
+    /// Aucune connexion n'est ouverte : voir `core::startup::connect_database`.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::from_source(&ConfigSource::from_env()?)
+    }
 
-        let discord_bot_token = env::var("DISCORD_BOT_TOKEN")
-            .map_err(|_| ConfigError::MissingVariable("DISCORD_BOT_TOKEN".to_string()))?;
+    /// Construit la configuration typée depuis les couches données, sans IO réseau
+    ///
+    /// Les sections du fichier sont surchargées champ par champ par l'environnement et
+    /// `--set`, puis chaque sous-système vérifie ses propres valeurs ; les identifiants
+    /// osu! et le token Discord ne sont exigés que par `Config::validate`.
+    pub fn from_source(source: &ConfigSource) -> Result<Self, ConfigError> {
+        let settings = source.settings()?;
 
-        let database_config = DatabaseConfig::load();
-        let mut database = DatabaseManager::new();
-        database.connect(&database_config).await.unwrap();
         Ok(Config {
-            database: database,
-            osu_client_id,
-            osu_client_secret,
-            discord_bot_token,
+            database: DatabaseManager::new(),
+            database_options: load_database_options(&settings),
+            rate: load_rate_options(&settings)?,
+            osu_api: load_osu_api_options(&settings)?,
+            discovery: load_discovery_options(&settings)?,
+            refresh: load_refresh_options(&settings)?,
+            server: load_server_options(&settings)?,
+            bot: load_bot_options(&settings),
+            worker: load_worker_options(&settings),
+            storage: load_storage_options(&settings),
         })
     }
 
-    /// Charge la configuration avec des valeurs par défaut pour les variables manquantes
-    #[allow(dead_code)]
-    pub async fn load_with_defaults() -> Result<Self, ConfigError> {
-        // Charger le fichier .env s'il existe
-        dotenv().ok();
-
-        let osu_client_id = env::var("OSU_CLIENT_ID").unwrap_or_else(|_| "".to_string());
-
-        let osu_client_secret = env::var("OSU_CLIENT_SECRET").unwrap_or_else(|_| "".to_string());
-
-        let discord_bot_token = env::var("DISCORD_BOT_TOKEN").unwrap_or_else(|_| "".to_string());
-
-        let database_config = DatabaseConfig::load();
-        let mut database = DatabaseManager::new();
-        database.connect(&database_config).await.unwrap();
-        Ok(Config {
-            database: database,
-            osu_client_id,
-            osu_client_secret,
-            discord_bot_token,
-        })
+    /// Charge uniquement les options de rate, sans identifiants ni base de données
+    pub fn load_rate_options(source: &ConfigSource) -> Result<RateOptions, ConfigError> {
+        load_rate_options(&source.settings()?)
     }
 }
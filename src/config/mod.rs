@@ -1,13 +1,47 @@
+mod bot;
+mod check;
+mod database;
 mod default;
+mod discovery;
+mod env;
+mod file;
 mod load;
+mod osu_api;
+mod rate;
+mod refresh;
+mod server;
+mod source;
+mod storage;
+mod validate;
+mod worker;
+use crate::api::osu::OsuApiOptions;
+use crate::bot::BotOptions;
+use crate::core::discovery::DiscoveryOptions;
+use crate::core::refresh::RefreshOptions;
+use crate::core::startup::DatabaseOptions;
+use crate::core::worker::WorkerOptions;
+use crate::server::ServerOptions;
+use crate::utils::rate::file_manager::StorageOptions;
+use crate::utils::rate::options::RateOptions;
 use db::db::DatabaseManager;
 
+pub use check::EffectiveSetting;
+pub use file::ConfigFile;
+pub(crate) use rate::parse_centirate;
+pub use source::{ConfigSource, Origin};
+pub use validate::Subsystem;
+
 #[derive(Debug, Clone)]
 pub struct Config {
+    /// Non connectée tant que `core::startup::connect_database` n'a pas été appelé
     pub database: DatabaseManager,
-    #[allow(dead_code)]
-    pub osu_client_id: String,
-    #[allow(dead_code)]
-    pub osu_client_secret: String,
-    pub discord_bot_token: String,
+    pub database_options: DatabaseOptions,
+    pub rate: RateOptions,
+    pub osu_api: OsuApiOptions,
+    pub discovery: DiscoveryOptions,
+    pub refresh: RefreshOptions,
+    pub server: ServerOptions,
+    pub bot: BotOptions,
+    pub worker: WorkerOptions,
+    pub storage: StorageOptions,
 }
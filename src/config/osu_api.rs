@@ -0,0 +1,66 @@
+use crate::api::cache::ApiCacheOptions;
+use crate::api::osu::{OsuApiOptions, OsuCredentials};
+use crate::config::env::optional;
+use crate::config::file::{ConfigFile, OsuSection};
+use crate::errors::config::ConfigError;
+use std::time::Duration;
+
+/// Charge les options du client osu! API depuis la configuration
+pub(crate) fn load_osu_api_options(settings: &ConfigFile) -> Result<OsuApiOptions, ConfigError> {
+    let default = OsuApiOptions::default();
+    let osu = &settings.osu;
+
+    Ok(OsuApiOptions {
+        credentials: load_credentials(osu)?,
+        requests_per_minute: osu
+            .requests_per_minute
+            .unwrap_or(default.requests_per_minute),
+        max_retries: osu.max_retries.unwrap_or(default.max_retries),
+        retry_base_delay_ms: osu
+            .retry_base_delay_ms
+            .unwrap_or(default.retry_base_delay_ms),
+        max_retry_delay: osu
+            .max_retry_delay_secs
+            .map_or(default.max_retry_delay, Duration::from_secs),
+        download_timeout: osu
+            .download_timeout_secs
+            .map_or(default.download_timeout, Duration::from_secs),
+        cache: load_cache_options(osu, default.cache),
+        fixtures_dir: optional(&osu.fixtures_dir, None),
+    })
+}
+
+/// Les deux variables vont ensemble ; leur absence n'est une erreur que pour les
+/// commandes qui appellent réellement l'API (voir `Config::validate`)
+fn load_credentials(osu: &OsuSection) -> Result<Option<OsuCredentials>, ConfigError> {
+    let client_secret = optional(&osu.client_secret, None);
+
+    match (osu.client_id, client_secret) {
+        (None, None) => Ok(None),
+        (Some(_), None) => Err(ConfigError::missing(
+            "OSU_CLIENT_SECRET",
+            "OSU_CLIENT_ID is set",
+        )),
+        (None, Some(_)) => Err(ConfigError::missing(
+            "OSU_CLIENT_ID",
+            "OSU_CLIENT_SECRET is set",
+        )),
+        (Some(client_id), Some(client_secret)) => Ok(Some(OsuCredentials {
+            client_id,
+            client_secret,
+        })),
+    }
+}
+
+/// Une variable `OSU_API_CACHE_DIR` vide désactive le cache
+fn load_cache_options(osu: &OsuSection, default: ApiCacheOptions) -> ApiCacheOptions {
+    ApiCacheOptions {
+        dir: optional(&osu.cache_dir, default.dir),
+        final_ttl: osu
+            .cache_final_ttl_secs
+            .map_or(default.final_ttl, Duration::from_secs),
+        short_ttl: osu
+            .cache_short_ttl_secs
+            .map_or(default.short_ttl, Duration::from_secs),
+    }
+}
@@ -0,0 +1,101 @@
+use crate::config::env::{optional, parsed_or};
+use crate::config::file::{ConfigFile, Scalar};
+use crate::errors::config::ConfigError;
+use crate::utils::calculator::CalculatorOptions;
+use crate::utils::rate::compression::{CompressionCodec, CompressionOptions};
+use crate::utils::rate::difficulty::{DifficultyOptions, OdAdjustment};
+use crate::utils::rate::metadata::RateMetadata;
+use crate::utils::rate::options::{RateOptions, DEFAULT_CENTIRATES};
+
+/// Charge les options de génération des rates depuis la configuration
+///
+/// Une variable absente garde la valeur par défaut, une variable vide désactive la réécriture.
+pub(crate) fn load_rate_options(settings: &ConfigFile) -> Result<RateOptions, ConfigError> {
+    let default = RateMetadata::default();
+    let rate = &settings.rate;
+
+    let metadata = RateMetadata {
+        beatmap_id: rate.beatmap_id.unwrap_or(default.beatmap_id),
+        beatmap_set_id: rate.beatmapset_id.unwrap_or(default.beatmap_set_id),
+        tag: optional(&rate.tag, default.tag),
+        creator_suffix: optional(&rate.creator_suffix, default.creator_suffix),
+    };
+
+    let difficulty = DifficultyOptions {
+        od: od_adjustment("RATE_OD", rate.od.as_ref())?,
+        hp: optional_f32("RATE_HP", rate.hp.as_ref())?,
+    };
+
+    let compression = CompressionOptions {
+        codec: parsed_or(
+            "RATE_COMPRESSION_CODEC",
+            &rate.compression_codec,
+            CompressionCodec::default(),
+        )?,
+        level: rate.compression_level,
+    };
+
+    let default_calculators = CalculatorOptions::default();
+    let calculators = CalculatorOptions {
+        osu: settings.calculators.osu.unwrap_or(default_calculators.osu),
+        sunnyxxy: settings
+            .calculators
+            .sunnyxxy
+            .unwrap_or(default_calculators.sunnyxxy),
+    };
+
+    Ok(RateOptions {
+        centirates: centirates("RATE_RATES", rate.rates.as_deref())?,
+        calculators,
+        metadata,
+        difficulty,
+        compression,
+    })
+}
+
+/// Liste de rates (ex: `[1.0, 1.1, 1.2]`)
+fn centirates(name: &str, rates: Option<&[f64]>) -> Result<Vec<i32>, ConfigError> {
+    let Some(rates) = rates else {
+        return Ok(DEFAULT_CENTIRATES.to_vec());
+    };
+
+    rates
+        .iter()
+        .map(|rate| centirate(*rate))
+        .collect::<Option<Vec<i32>>>()
+        .ok_or_else(|| ConfigError::invalid(name, "expected rates from 0.7 to 2.0 by steps of 0.1"))
+}
+
+/// Convertit une rate (`1.2`) en centirate (`120`), limitée aux rates que minacalc calcule
+pub(crate) fn parse_centirate(rate: &str) -> Option<i32> {
+    centirate(rate.parse::<f64>().ok()?)
+}
+
+fn centirate(rate: f64) -> Option<i32> {
+    let centirate = (rate * 100.0).round() as i32;
+    ((70..=200).contains(&centirate) && centirate % 10 == 0).then_some(centirate)
+}
+
+/// `keep` (défaut), `constant` pour garder les fenêtres de jugement, ou une valeur absolue
+fn od_adjustment(name: &str, value: Option<&Scalar>) -> Result<OdAdjustment, ConfigError> {
+    match value {
+        Some(Scalar::Number(value)) => Ok(OdAdjustment::Absolute(*value as f32)),
+        None => Ok(OdAdjustment::Keep),
+        Some(Scalar::Text(text)) => match text.as_str() {
+            "" | "keep" => Ok(OdAdjustment::Keep),
+            "constant" => Ok(OdAdjustment::ConstantWindow),
+            _ => Err(ConfigError::parse(name, text, "keep, constant or a number")),
+        },
+    }
+}
+
+fn optional_f32(name: &str, value: Option<&Scalar>) -> Result<Option<f32>, ConfigError> {
+    match value {
+        Some(Scalar::Number(value)) => Ok(Some(*value as f32)),
+        None => Ok(None),
+        Some(Scalar::Text(text)) => match text.as_str() {
+            "" | "keep" => Ok(None),
+            _ => Err(ConfigError::parse(name, text, "keep or a number")),
+        },
+    }
+}
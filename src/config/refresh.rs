@@ -0,0 +1,36 @@
+use crate::config::file::ConfigFile;
+use crate::core::refresh::RefreshOptions;
+use crate::errors::config::ConfigError;
+use crate::utils::rank_status_from_string;
+use std::time::Duration;
+
+/// Charge les options du rafraîchissement des statuts depuis la configuration
+pub(crate) fn load_refresh_options(settings: &ConfigFile) -> Result<RefreshOptions, ConfigError> {
+    let default = RefreshOptions::default();
+    let refresh = &settings.refresh;
+
+    let statuses = match &refresh.statuses {
+        Some(statuses) => {
+            let statuses: Vec<String> = statuses.iter().map(|s| s.trim().to_string()).collect();
+            if let Some(unknown) = statuses
+                .iter()
+                .find(|s| rank_status_from_string(s).is_none())
+            {
+                return Err(ConfigError::invalid(
+                    "REFRESH_STATUSES",
+                    format!("unknown rank status {:?}", unknown),
+                ));
+            }
+            statuses
+        }
+        None => default.statuses,
+    };
+
+    Ok(RefreshOptions {
+        enabled: refresh.enabled.unwrap_or(default.enabled),
+        interval: refresh
+            .interval_secs
+            .map_or(default.interval, Duration::from_secs),
+        statuses,
+    })
+}
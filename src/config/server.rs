@@ -0,0 +1,21 @@
+use crate::config::env::parsed_or;
+use crate::config::file::ConfigFile;
+use crate::errors::config::ConfigError;
+use crate::server::ServerOptions;
+
+/// Charge les options du serveur HTTP depuis la configuration
+pub(crate) fn load_server_options(settings: &ConfigFile) -> Result<ServerOptions, ConfigError> {
+    let default = ServerOptions::default();
+    let server = &settings.server;
+
+    Ok(ServerOptions {
+        bind: parsed_or("SERVER_BIND", &server.bind, default.bind)?,
+        max_body_bytes: server.max_body_bytes.unwrap_or(default.max_body_bytes),
+        requests_per_minute_per_ip: server
+            .requests_per_minute
+            .unwrap_or(default.requests_per_minute_per_ip),
+        max_concurrent_calculations: server
+            .max_concurrent_calculations
+            .unwrap_or(default.max_concurrent_calculations),
+    })
+}
@@ -0,0 +1,205 @@
+use crate::config::file::{read_config_file, ConfigFile};
+use crate::errors::config::ConfigError;
+use dotenvy::dotenv;
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Fichier lu par défaut s'il existe dans le dossier courant
+pub const DEFAULT_CONFIG_FILE: &str = "pendora.toml";
+
+/// Variable d'environnement pointant vers le fichier de configuration
+pub const CONFIG_FILE_VARIABLE: &str = "PENDORA_CONFIG";
+
+/// Réglages connus : nom de variable d'environnement et clé `section.champ` du fichier
+pub const SETTINGS: &[(&str, &str)] = &[
+    ("DATABASE_URL", "database.url"),
+    (
+        "DATABASE_CONNECT_MAX_ATTEMPTS",
+        "database.connect_max_attempts",
+    ),
+    ("DATABASE_CONNECT_BACKOFF_MS", "database.connect_backoff_ms"),
+    (
+        "DATABASE_CONNECT_MAX_BACKOFF_SECS",
+        "database.connect_max_backoff_secs",
+    ),
+    ("OSU_CLIENT_ID", "osu.client_id"),
+    ("OSU_CLIENT_SECRET", "osu.client_secret"),
+    ("OSU_API_REQUESTS_PER_MINUTE", "osu.requests_per_minute"),
+    ("OSU_API_MAX_RETRIES", "osu.max_retries"),
+    ("OSU_API_RETRY_BASE_DELAY_MS", "osu.retry_base_delay_ms"),
+    ("OSU_API_MAX_RETRY_DELAY_SECS", "osu.max_retry_delay_secs"),
+    ("OSU_API_DOWNLOAD_TIMEOUT_SECS", "osu.download_timeout_secs"),
+    ("OSU_API_FIXTURES_DIR", "osu.fixtures_dir"),
+    ("OSU_API_CACHE_DIR", "osu.cache_dir"),
+    ("OSU_API_CACHE_FINAL_TTL_SECS", "osu.cache_final_ttl_secs"),
+    ("OSU_API_CACHE_SHORT_TTL_SECS", "osu.cache_short_ttl_secs"),
+    ("RATE_RATES", "rate.rates"),
+    ("RATE_BEATMAP_ID", "rate.beatmap_id"),
+    ("RATE_BEATMAPSET_ID", "rate.beatmapset_id"),
+    ("RATE_TAG", "rate.tag"),
+    ("RATE_CREATOR_SUFFIX", "rate.creator_suffix"),
+    ("RATE_OD", "rate.od"),
+    ("RATE_HP", "rate.hp"),
+    ("RATE_COMPRESSION_CODEC", "rate.compression_codec"),
+    ("RATE_COMPRESSION_LEVEL", "rate.compression_level"),
+    ("CALCULATOR_OSU_ENABLED", "calculators.osu"),
+    ("CALCULATOR_SUNNYXXY_ENABLED", "calculators.sunnyxxy"),
+    ("WORKER_POLL_INTERVAL_SECS", "worker.poll_interval_secs"),
+    ("WORKER_ERROR_BACKOFF_SECS", "worker.error_backoff_secs"),
+    (
+        "WORKER_SHUTDOWN_TIMEOUT_SECS",
+        "worker.shutdown_timeout_secs",
+    ),
+    ("STORAGE_BEATMAP_ROOT", "storage.beatmap_root"),
+    ("DISCOVERY_ENABLED", "discovery.enabled"),
+    ("DISCOVERY_INTERVAL_SECS", "discovery.interval_secs"),
+    ("DISCOVERY_MAX_PAGES", "discovery.max_pages"),
+    ("DISCOVERY_STATUSES", "discovery.statuses"),
+    ("DISCOVERY_CURSOR_PATH", "discovery.cursor_path"),
+    ("REFRESH_ENABLED", "refresh.enabled"),
+    ("REFRESH_INTERVAL_SECS", "refresh.interval_secs"),
+    ("REFRESH_STATUSES", "refresh.statuses"),
+    ("SERVER_BIND", "server.bind"),
+    ("SERVER_MAX_BODY_BYTES", "server.max_body_bytes"),
+    ("SERVER_REQUESTS_PER_MINUTE", "server.requests_per_minute"),
+    (
+        "SERVER_MAX_CONCURRENT_CALCULATIONS",
+        "server.max_concurrent_calculations",
+    ),
+    ("DISCORD_ENABLED", "discord.enabled"),
+    ("DISCORD_BOT_TOKEN", "discord.bot_token"),
+    ("DISCORD_GUILD_ID", "discord.guild_id"),
+    ("DISCORD_RESULTS_CHANNEL_ID", "discord.results_channel_id"),
+];
+
+/// Couche d'où provient une valeur, de la plus prioritaire à la moins prioritaire
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// `--set NAME=VALUE` en ligne de commande
+    CommandLine,
+    Environment,
+    File(PathBuf),
+    Default,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::CommandLine => write!(f, "--set"),
+            Origin::Environment => write!(f, "environment"),
+            Origin::File(path) => write!(f, "{}", path.display()),
+            Origin::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// Couches de la configuration : fichier TOML typé, environnement et `--set`
+///
+/// Priorité : `--set` > variables d'environnement (et `.env`) > fichier TOML > défauts.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSource {
+    overrides: HashMap<String, String>,
+    file: ConfigFile,
+    /// Fichier tel qu'écrit, pour `config check`
+    document: toml::Table,
+    file_path: Option<PathBuf>,
+}
+
+impl ConfigSource {
+    /// Construit les couches à partir d'un fichier explicite et des `--set` de la CLI
+    ///
+    /// Sans fichier explicite, `PENDORA_CONFIG` puis `pendora.toml` sont essayés.
+    pub fn new(config_file: Option<&Path>, overrides: &[String]) -> Result<Self, ConfigError> {
+        // Charger le fichier .env s'il existe
+        dotenv().ok();
+
+        let file_path = match config_file {
+            Some(path) => Some(path.to_path_buf()),
+            None => match env::var(CONFIG_FILE_VARIABLE) {
+                Ok(path) if !path.is_empty() => Some(PathBuf::from(path)),
+                _ => Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|path| path.is_file()),
+            },
+        };
+        let (file, document) = match &file_path {
+            Some(path) => read_config_file(path)?,
+            None => Default::default(),
+        };
+
+        let overrides = overrides
+            .iter()
+            .map(|entry| match entry.split_once('=') {
+                Some((name, value)) if is_setting(name.trim()) => {
+                    Ok((name.trim().to_string(), value.to_string()))
+                }
+                Some((name, _)) => Err(ConfigError::invalid(
+                    "--set",
+                    format!("unknown setting {:?}", name.trim()),
+                )),
+                _ => Err(ConfigError::invalid(
+                    "--set",
+                    format!("expected NAME=VALUE, got {:?}", entry),
+                )),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            overrides,
+            file,
+            document,
+            file_path,
+        })
+    }
+
+    /// Environnement et `pendora.toml` uniquement, sans surcharge de la CLI
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::new(None, &[])
+    }
+
+    pub fn file_path(&self) -> Option<&Path> {
+        self.file_path.as_deref()
+    }
+
+    /// Sections du fichier, surchargées par l'environnement puis par `--set`
+    pub fn settings(&self) -> Result<ConfigFile, ConfigError> {
+        let mut settings = self.file.clone();
+        for (name, _) in SETTINGS {
+            if let Ok(value) = env::var(name) {
+                settings.set(name, &value)?;
+            }
+        }
+        for (name, value) in &self.overrides {
+            settings.set(name, value)?;
+        }
+        Ok(settings)
+    }
+
+    /// Valeur brute retenue et sa couche, None si aucune couche ne la définit
+    pub fn lookup(&self, name: &str) -> Option<(String, Origin)> {
+        if let Some(value) = self.overrides.get(name) {
+            return Some((value.clone(), Origin::CommandLine));
+        }
+        if let Ok(value) = env::var(name) {
+            return Some((value, Origin::Environment));
+        }
+        let (_, key) = SETTINGS.iter().find(|(setting, _)| *setting == name)?;
+        let (section, field) = key.split_once('.')?;
+        let value = self.document.get(section)?.get(field)?;
+        let path = self.file_path.clone().unwrap_or_default();
+        Some((display(value), Origin::File(path)))
+    }
+}
+
+fn is_setting(name: &str) -> bool {
+    SETTINGS.iter().any(|(setting, _)| *setting == name)
+}
+
+/// Valeur du fichier sous la forme d'une variable d'environnement
+fn display(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(text) => text.clone(),
+        toml::Value::Array(items) => items.iter().map(display).collect::<Vec<_>>().join(","),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,12 @@
+use crate::config::env::or_default;
+use crate::config::file::ConfigFile;
+use crate::utils::rate::file_manager::StorageOptions;
+
+/// Charge les chemins de stockage depuis la configuration
+pub(crate) fn load_storage_options(settings: &ConfigFile) -> StorageOptions {
+    let default = StorageOptions::default();
+
+    StorageOptions {
+        beatmap_root: or_default(&settings.storage.beatmap_root, default.beatmap_root),
+    }
+}
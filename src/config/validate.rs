@@ -0,0 +1,116 @@
+use crate::config::Config;
+use crate::errors::config::ConfigError;
+use crate::utils::rate::compression::CompressionCodec;
+
+/// Sous-systèmes ayant chacun leurs propres variables obligatoires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    /// Worker, discovery et refresh : appels à l'API osu!
+    Worker,
+    /// Serveur HTTP
+    Api,
+    /// Bot Discord, uniquement si activé
+    Bot,
+    /// Génération et stockage des rates
+    Storage,
+}
+
+impl Config {
+    /// Vérifie au démarrage ce dont un sous-système a besoin, plutôt qu'au premier appel
+    pub fn validate(&self, subsystem: Subsystem) -> Result<(), ConfigError> {
+        match subsystem {
+            Subsystem::Worker => {
+                self.require_osu_credentials()?;
+                positive(
+                    "WORKER_POLL_INTERVAL_SECS",
+                    self.worker.poll_interval.as_secs(),
+                )?;
+                positive(
+                    "OSU_API_DOWNLOAD_TIMEOUT_SECS",
+                    self.osu_api.download_timeout.as_secs(),
+                )
+            }
+            Subsystem::Api => {
+                self.require_osu_credentials()?;
+                positive("SERVER_MAX_BODY_BYTES", self.server.max_body_bytes as u64)?;
+                positive(
+                    "SERVER_REQUESTS_PER_MINUTE",
+                    self.server.requests_per_minute_per_ip as u64,
+                )?;
+                positive(
+                    "SERVER_MAX_CONCURRENT_CALCULATIONS",
+                    self.server.max_concurrent_calculations as u64,
+                )
+            }
+            Subsystem::Bot => {
+                if self.bot.enabled && self.bot.token.is_none() {
+                    return Err(ConfigError::missing(
+                        "DISCORD_BOT_TOKEN",
+                        "DISCORD_ENABLED is true",
+                    ));
+                }
+                Ok(())
+            }
+            Subsystem::Storage => {
+                if self.rate.centirates.is_empty() {
+                    return Err(ConfigError::invalid(
+                        "RATE_RATES",
+                        "at least one rate is required",
+                    ));
+                }
+                if self.storage.beatmap_root.is_file() {
+                    return Err(ConfigError::invalid(
+                        "STORAGE_BEATMAP_ROOT",
+                        format!("{} is a file", self.storage.beatmap_root.display()),
+                    ));
+                }
+                compression_level(self.rate.compression.codec, self.rate.compression.level)
+            }
+        }
+    }
+
+    /// Les identifiants ne sont pas requis quand les réponses viennent de fixtures
+    pub fn require_osu_credentials(&self) -> Result<(), ConfigError> {
+        if self.osu_api.fixtures_dir.is_some() || self.osu_api.credentials.is_some() {
+            return Ok(());
+        }
+        Err(ConfigError::missing(
+            "OSU_CLIENT_ID",
+            "required to call the osu! API without OSU_API_FIXTURES_DIR",
+        ))
+    }
+}
+
+fn positive(name: &str, value: u64) -> Result<(), ConfigError> {
+    if value == 0 {
+        return Err(ConfigError::invalid(name, "must be greater than 0"));
+    }
+    Ok(())
+}
+
+/// Bornes acceptées par chaque codec
+fn compression_level(codec: CompressionCodec, level: Option<i32>) -> Result<(), ConfigError> {
+    let Some(level) = level else {
+        return Ok(());
+    };
+
+    let range = match codec {
+        CompressionCodec::Brotli => 0..=11,
+        CompressionCodec::Zstd => 1..=22,
+        CompressionCodec::Gzip => 0..=9,
+        CompressionCodec::None => return Ok(()),
+    };
+    if !range.contains(&level) {
+        return Err(ConfigError::invalid(
+            "RATE_COMPRESSION_LEVEL",
+            format!(
+                "{} for {:?}, expected {} to {}",
+                level,
+                codec,
+                range.start(),
+                range.end()
+            ),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use crate::config::file::ConfigFile;
+use crate::core::worker::WorkerOptions;
+use std::time::Duration;
+
+/// Charge la cadence du worker depuis la configuration
+pub(crate) fn load_worker_options(settings: &ConfigFile) -> WorkerOptions {
+    let default = WorkerOptions::default();
+    let worker = &settings.worker;
+
+    WorkerOptions {
+        poll_interval: worker
+            .poll_interval_secs
+            .map_or(default.poll_interval, Duration::from_secs),
+        error_backoff: worker
+            .error_backoff_secs
+            .map_or(default.error_backoff, Duration::from_secs),
+        shutdown_timeout: worker
+            .shutdown_timeout_secs
+            .map_or(default.shutdown_timeout, Duration::from_secs),
+    }
+}
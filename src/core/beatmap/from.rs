@@ -1,6 +1,11 @@
 use crate::utils::rank_status_to_string;
+use bigdecimal::ToPrimitive;
+use db::models::beatmaps::beatmap::BeatmapRow;
 use dto::models::beatmaps::full::types::Beatmap;
-use rosu_v2::prelude::BeatmapExtended;
+use dto::models::rate::Rates;
+use rosu_map::section::hit_objects::HitObjectKind;
+use rosu_map::Beatmap as RmBeatmap;
+use rosu_v2::prelude::{BeatmapExtended, RankStatus};
 
 pub fn beatmap_from_beatmap_extended(b: &BeatmapExtended) -> Beatmap {
     Beatmap {
@@ -18,7 +23,59 @@ pub fn beatmap_from_beatmap_extended(b: &BeatmapExtended) -> Beatmap {
         hp: b.hp.clone() as f64,
         mode: b.mode.clone() as i32,
         status: rank_status_to_string(&b.status.clone()),
-        main_pattern: serde_json::to_value("[]").unwrap(),
+        main_pattern: serde_json::json!([]),
         rates: Vec::new(),
     }
 }
+
+/// Construit le DTO à partir du `.osu` seul, sans métadonnées de l'API osu!
+pub fn beatmap_from_rosu_map(map: &RmBeatmap) -> Beatmap {
+    let count_of = |kind: fn(&HitObjectKind) -> bool| {
+        map.hit_objects.iter().filter(|h| kind(&h.kind)).count() as i32
+    };
+    // Comme osu! : une note compte pour 1, une long note pour 2 (début et fin)
+    let max_combo =
+        map.hit_objects.len() as i32 + count_of(|k| matches!(k, HitObjectKind::Hold(_)));
+
+    Beatmap {
+        id: None,
+        osu_id: (map.beatmap_id > 0).then_some(map.beatmap_id),
+        beatmapset_id: None,
+        difficulty: map.version.clone(),
+        count_circles: count_of(|k| matches!(k, HitObjectKind::Circle(_))),
+        // L'API compte les long notes comme des sliders
+        count_sliders: count_of(|k| matches!(k, HitObjectKind::Slider(_) | HitObjectKind::Hold(_))),
+        count_spinners: count_of(|k| matches!(k, HitObjectKind::Spinner(_))),
+        max_combo,
+        cs: map.circle_size as f64,
+        ar: map.approach_rate as f64,
+        od: map.overall_difficulty as f64,
+        hp: map.hp_drain_rate as f64,
+        mode: map.mode as i32,
+        // Statut réel inconnu hors ligne, le refresh le corrigera si la map existe sur osu!
+        status: rank_status_to_string(&RankStatus::Pending),
+        main_pattern: serde_json::json!([]),
+        rates: Vec::new(),
+    }
+}
+
+pub fn beatmap_from_row(row: &BeatmapRow, rates: Vec<Rates>) -> Beatmap {
+    Beatmap {
+        id: Some(row.id),
+        osu_id: row.osu_id,
+        beatmapset_id: row.beatmapset_id,
+        difficulty: row.difficulty.clone(),
+        count_circles: row.count_circles,
+        count_sliders: row.count_sliders,
+        count_spinners: row.count_spinners,
+        max_combo: row.max_combo,
+        cs: row.cs.to_f64().unwrap_or_default(),
+        ar: row.ar.to_f64().unwrap_or_default(),
+        od: row.od.to_f64().unwrap_or_default(),
+        hp: row.hp.to_f64().unwrap_or_default(),
+        mode: row.mode,
+        status: row.status.clone(),
+        main_pattern: row.main_pattern.clone(),
+        rates,
+    }
+}
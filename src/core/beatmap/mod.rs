@@ -1 +1,2 @@
 pub mod from;
+pub mod timings;
@@ -0,0 +1,81 @@
+use rosu_map::section::hit_objects::HitObjectKind;
+use rosu_map::Beatmap as RmBeatmap;
+use rosu_v2::prelude::BeatmapExtended;
+
+/// Durées et BPM d'une beatmap, nécessaires au calcul des rates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeatmapTimings {
+    /// Durée de jeu en secondes, pauses exclues
+    pub drain_time: f64,
+    /// Durée totale en secondes
+    pub total_time: f64,
+    pub bpm: f32,
+}
+
+impl BeatmapTimings {
+    pub fn from_beatmap_extended(b: &BeatmapExtended) -> Self {
+        Self {
+            drain_time: b.seconds_drain as f64,
+            total_time: b.seconds_total as f64,
+            bpm: b.bpm,
+        }
+    }
+
+    /// Calcule les durées et le BPM à partir du contenu du `.osu`, comme le fait osu!
+    pub fn from_rosu_map(map: &RmBeatmap) -> Self {
+        let first_time = map.hit_objects.first().map(|h| h.start_time).unwrap_or(0.0);
+        let last_time = map
+            .hit_objects
+            .iter()
+            .map(|h| match &h.kind {
+                HitObjectKind::Hold(hold) => h.start_time + hold.duration,
+                HitObjectKind::Spinner(spinner) => h.start_time + spinner.duration,
+                _ => h.start_time,
+            })
+            .fold(0.0, f64::max);
+
+        let break_time: f64 = map.breaks.iter().map(|b| b.end_time - b.start_time).sum();
+
+        Self {
+            drain_time: ((last_time - first_time - break_time) / 1000.0)
+                .max(0.0)
+                .round(),
+            total_time: (last_time / 1000.0).round(),
+            bpm: Self::main_bpm(map, last_time) as f32,
+        }
+    }
+
+    /// BPM du timing point qui couvre la plus longue durée de la map
+    fn main_bpm(map: &RmBeatmap, last_time: f64) -> f64 {
+        let timing_points = &map.control_points.timing_points;
+        let mut durations: Vec<(f64, f64)> = Vec::new();
+
+        for (i, point) in timing_points.iter().enumerate() {
+            if point.time > last_time {
+                break;
+            }
+            let end = timing_points
+                .get(i + 1)
+                .map(|next| next.time)
+                .unwrap_or(last_time)
+                .min(last_time);
+            let duration = if i == 0 { end } else { end - point.time };
+
+            match durations
+                .iter_mut()
+                .find(|(beat_len, _)| (beat_len - point.beat_len).abs() < 1e-3)
+            {
+                Some((_, total)) => *total += duration,
+                None => durations.push((point.beat_len, duration)),
+            }
+        }
+
+        durations
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .or_else(|| timing_points.first().map(|p| (p.beat_len, 0.0)))
+            .filter(|(beat_len, _)| *beat_len > 0.0)
+            .map(|(beat_len, _)| 60_000.0 / beat_len)
+            .unwrap_or(0.0)
+    }
+}
@@ -1,5 +1,7 @@
 use chrono::DateTime;
-use dto::models::beatmaps::full::types::Beatmapset;
+use db::models::beatmaps::beatmapset::BeatmapsetRow;
+use dto::models::beatmaps::full::types::{Beatmap, Beatmapset};
+use rosu_map::Beatmap as RmBeatmap;
 use rosu_v2::prelude::BeatmapsetExtended;
 
 pub fn beatmapset_from_beatmapset_extended(beatmapset: &BeatmapsetExtended) -> Beatmapset {
@@ -38,3 +40,52 @@ pub fn beatmapset_from_beatmapset_extended(beatmapset: &BeatmapsetExtended) -> B
         ),
     }
 }
+
+/// Construit le DTO à partir du `.osu` seul, sans métadonnées de l'API osu!
+pub fn beatmapset_from_rosu_map(map: &RmBeatmap) -> Beatmapset {
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+
+    Beatmapset {
+        id: None,
+        osu_id: (map.beatmap_set_id > 0).then_some(map.beatmap_set_id),
+        artist: map.artist.clone(),
+        artist_unicode: non_empty(&map.artist_unicode),
+        title: map.title.clone(),
+        title_unicode: non_empty(&map.title_unicode),
+        creator: map.creator.clone(),
+        source: non_empty(&map.source),
+        tags: non_empty(&map.tags),
+        has_video: false,
+        has_storyboard: false,
+        is_explicit: false,
+        is_featured: false,
+        cover_url: None,
+        preview_url: None,
+        osu_file_url: None,
+        beatmaps: Vec::new(),
+        osu_status_changed_at: None,
+    }
+}
+
+pub fn beatmapset_from_row(row: &BeatmapsetRow, beatmaps: Vec<Beatmap>) -> Beatmapset {
+    Beatmapset {
+        id: Some(row.id),
+        osu_id: row.osu_id,
+        artist: row.artist.clone(),
+        artist_unicode: row.artist_unicode.clone(),
+        title: row.title.clone(),
+        title_unicode: row.title_unicode.clone(),
+        creator: row.creator.clone(),
+        source: row.source.clone(),
+        tags: row.tags.as_ref().map(|tags| tags.join(" ")),
+        has_video: row.has_video,
+        has_storyboard: row.has_storyboard,
+        is_explicit: row.is_explicit,
+        is_featured: row.is_featured,
+        cover_url: row.cover_url.clone(),
+        preview_url: row.preview_url.clone(),
+        osu_file_url: row.osu_file_url.clone(),
+        beatmaps,
+        osu_status_changed_at: row.osu_status_changed_at,
+    }
+}
@@ -0,0 +1,242 @@
+use crate::api::OsuApi;
+use crate::core::enqueue::enqueue_beatmaps;
+use crate::core::shutdown::Shutdown;
+use crate::utils::rank_status_to_string;
+use db::db::DatabaseManager;
+use rosu_v2::prelude::{BeatmapsetExtended, RankStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Settings of the periodic discovery of new mania beatmapsets
+#[derive(Debug, Clone)]
+pub struct DiscoveryOptions {
+    pub enabled: bool,
+    pub interval: Duration,
+    /// Pages fetched per status and run (also bounds the first run, without cursor)
+    pub max_pages: u32,
+    /// File where the per-status cursor is persisted
+    pub cursor_path: PathBuf,
+    pub statuses: Vec<RankStatus>,
+}
+
+impl Default for DiscoveryOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(3600),
+            max_pages: 5,
+            cursor_path: PathBuf::from("cache/discovery_cursor.json"),
+            statuses: vec![RankStatus::Ranked, RankStatus::Loved, RankStatus::Qualified],
+        }
+    }
+}
+
+/// Progress of the discovery, per rank status
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DiscoveryCursor {
+    /// Timestamp up to which every mapset has been enqueued
+    last_seen: HashMap<String, i64>,
+    /// Catch-up still running because a pass hit `max_pages` before `last_seen`
+    #[serde(default)]
+    backfill: HashMap<String, Backfill>,
+}
+
+/// Mapsets from `oldest` to `newest` are enqueued, the gap down to `last_seen` is not yet
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Backfill {
+    newest: i64,
+    oldest: i64,
+    /// Search page the next pass starts from
+    next_page: u32,
+}
+
+/// Outcome of walking search pages
+#[derive(Debug, Default)]
+struct Walk {
+    count: usize,
+    newest: Option<i64>,
+    oldest: Option<i64>,
+    next_page: u32,
+    /// `last_seen` or the end of the results was reached
+    complete: bool,
+}
+
+/// Periodically searches the osu! API for new mania beatmapsets and enqueues their hashes
+pub struct DiscoveryTask {
+    pub database: DatabaseManager,
+    pub osu_api: Arc<dyn OsuApi>,
+    pub options: DiscoveryOptions,
+}
+
+impl DiscoveryTask {
+    /// Runs a pass every `interval` until a shutdown is requested, which also cuts the
+    /// current pass short
+    pub async fn run(&self, shutdown: Shutdown) {
+        info!(
+            "Discovery task started, interval {}s",
+            self.options.interval.as_secs()
+        );
+
+        while !shutdown.is_requested() {
+            tokio::select! {
+                result = self.run_once() => {
+                    if let Err(e) = result {
+                        error!("Discovery run failed: {}", e);
+                    }
+                }
+                _ = shutdown.requested() => break,
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(self.options.interval) => {}
+                _ = shutdown.requested() => {}
+            }
+        }
+
+        info!("Discovery task stopped");
+    }
+
+    /// Run a single discovery pass over all configured statuses
+    pub async fn run_once(&self) -> anyhow::Result<usize> {
+        let mut cursor = self.load_cursor().await;
+        let mut total = 0;
+
+        for status in &self.options.statuses {
+            let key = rank_status_to_string(status);
+            let last_seen = cursor.last_seen.get(&key).copied();
+            let backfill = cursor.backfill.get(&key).copied();
+
+            // A catch-up resumes below what it already enqueued, newer mapsets wait for it
+            let walk = match backfill {
+                Some(backfill) => {
+                    self.walk_pages(
+                        *status,
+                        backfill.next_page,
+                        Some(backfill.oldest),
+                        last_seen,
+                    )
+                    .await?
+                }
+                None => self.walk_pages(*status, 1, None, last_seen).await?,
+            };
+            total += walk.count;
+
+            let newest = backfill.map(|backfill| backfill.newest).or(walk.newest);
+            let oldest = walk.oldest.or(backfill.map(|backfill| backfill.oldest));
+            match (newest, oldest) {
+                // The first run is bounded by `max_pages`, nothing older is looked for
+                (Some(newest), _) if walk.complete || last_seen.is_none() => {
+                    cursor.last_seen.insert(key.clone(), newest);
+                    cursor.backfill.remove(&key);
+                }
+                (Some(newest), Some(oldest)) => {
+                    debug!(
+                        "Discovery {:?}: max_pages reached before the cursor, resuming at page {}",
+                        status, walk.next_page
+                    );
+                    cursor.backfill.insert(
+                        key,
+                        Backfill {
+                            newest,
+                            oldest,
+                            next_page: walk.next_page,
+                        },
+                    );
+                }
+                _ => continue,
+            }
+            self.save_cursor(&cursor).await;
+        }
+
+        info!("Discovery run done: {} beatmaps enqueued", total);
+        Ok(total)
+    }
+
+    /// Walk `max_pages` search pages (most recent first) from `first_page` until reaching
+    /// `last_seen`, enqueueing mapsets older than `before`
+    async fn walk_pages(
+        &self,
+        status: RankStatus,
+        first_page: u32,
+        before: Option<i64>,
+        last_seen: Option<i64>,
+    ) -> anyhow::Result<Walk> {
+        let mut walk = Walk {
+            next_page: first_page,
+            ..Default::default()
+        };
+
+        for page in first_page..first_page + self.options.max_pages {
+            let mapsets = self.osu_api.search_mania_beatmapsets(status, page).await?;
+            walk.next_page = page + 1;
+            if mapsets.is_empty() {
+                walk.complete = true;
+                break;
+            }
+
+            for mapset in mapsets {
+                let timestamp = mapset_timestamp(&mapset);
+                if last_seen.is_some_and(|last| timestamp <= last) {
+                    walk.complete = true;
+                    break;
+                }
+                // Pages shift as maps get ranked, the top of a resumed page was already seen
+                if before.is_some_and(|before| timestamp >= before) {
+                    continue;
+                }
+
+                walk.newest = Some(walk.newest.map_or(timestamp, |n| n.max(timestamp)));
+                walk.oldest = Some(walk.oldest.map_or(timestamp, |o| o.min(timestamp)));
+                let beatmaps = mapset.maps.unwrap_or_default();
+                walk.count += enqueue_beatmaps(&self.database, &beatmaps).await?;
+            }
+
+            debug!(
+                "Discovery {:?} page {}: {} beatmaps enqueued so far",
+                status, page, walk.count
+            );
+            if walk.complete {
+                break;
+            }
+        }
+
+        Ok(walk)
+    }
+
+    async fn load_cursor(&self) -> DiscoveryCursor {
+        match tokio::fs::read(&self.options.cursor_path).await {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+                warn!("Invalid discovery cursor, starting over: {}", e);
+                DiscoveryCursor::default()
+            }),
+            Err(_) => DiscoveryCursor::default(),
+        }
+    }
+
+    async fn save_cursor(&self, cursor: &DiscoveryCursor) {
+        let path = &self.options.cursor_path;
+        if let Some(parent) = path.parent() {
+            let _ = tokio::fs::create_dir_all(parent).await;
+        }
+
+        let result = match serde_json::to_vec(cursor) {
+            Ok(data) => tokio::fs::write(path, data)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = result {
+            warn!("Failed to save discovery cursor {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn mapset_timestamp(mapset: &BeatmapsetExtended) -> i64 {
+    mapset
+        .ranked_date
+        .unwrap_or(mapset.last_updated)
+        .unix_timestamp()
+}
@@ -0,0 +1,109 @@
+use crate::api::OsuApi;
+use crate::errors::BeatmapWorkerError;
+use crate::store;
+use crate::utils::is_allowed_beatmap;
+use db::db::DatabaseManager;
+use db::models::beatmaps::beatmap::BeatmapRow;
+use rosu_v2::prelude::BeatmapExtended;
+use std::sync::OnceLock;
+use tokio::sync::Notify;
+use tracing::{debug, info};
+
+/// Postgres channel notified by a trigger for every new pending beatmap, the payload is the hash
+pub const PENDING_CHANNEL: &str = "pending_beatmap";
+
+static PENDING_WAKEUP: OnceLock<Notify> = OnceLock::new();
+
+/// In-process wakeup for a worker running in the same process as the enqueuer
+pub fn pending_wakeup() -> &'static Notify {
+    PENDING_WAKEUP.get_or_init(Notify::new)
+}
+
+/// Add a beatmap hash to the pending queue.
+pub async fn enqueue_hash(
+    database: &DatabaseManager,
+    osu_hash: &str,
+) -> Result<(), BeatmapWorkerError> {
+    let pool = database.get_pool();
+
+    store::pending::insert(pool, osu_hash)
+        .await
+        .map_err(|e| BeatmapWorkerError::DatabaseError(e.to_string()))?;
+    debug!("Enqueued hash {}", osu_hash);
+
+    // Workers of other processes are woken by the trigger of `migrations/0002_pending_notify.sql`
+    pending_wakeup().notify_one();
+    Ok(())
+}
+
+/// Resolve a beatmap id through the osu! API and enqueue its hash.
+/// Returns the number of hashes added to the queue.
+pub async fn enqueue_beatmap_id(
+    database: &DatabaseManager,
+    osu_api: &dyn OsuApi,
+    beatmap_id: i32,
+) -> Result<usize, BeatmapWorkerError> {
+    let beatmap = osu_api
+        .beatmap_by_osu_id(beatmap_id)
+        .await
+        .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?;
+
+    enqueue_beatmaps(database, std::slice::from_ref(&beatmap)).await
+}
+
+/// Resolve a beatmapset id through the osu! API and enqueue every eligible difficulty.
+/// Returns the number of hashes added to the queue.
+pub async fn enqueue_beatmapset_id(
+    database: &DatabaseManager,
+    osu_api: &dyn OsuApi,
+    beatmapset_id: u32,
+) -> Result<usize, BeatmapWorkerError> {
+    let beatmapset = osu_api
+        .beatmapset_by_osu_id(beatmapset_id)
+        .await
+        .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?;
+
+    let beatmaps = beatmapset.maps.unwrap_or_default();
+    let count = enqueue_beatmaps(database, &beatmaps).await?;
+    info!(
+        "Beatmapset {}: {} of {} difficulties enqueued",
+        beatmapset_id,
+        count,
+        beatmaps.len()
+    );
+    Ok(count)
+}
+
+/// Enqueue the hashes of eligible beatmaps that are not processed yet.
+pub async fn enqueue_beatmaps(
+    database: &DatabaseManager,
+    beatmaps: &[BeatmapExtended],
+) -> Result<usize, BeatmapWorkerError> {
+    let pool = database.get_pool();
+    let mut count = 0;
+
+    for beatmap in beatmaps {
+        if !is_allowed_beatmap(beatmap.mode, beatmap.cs).await {
+            debug!("Beatmap {} not allowed, skipping", beatmap.map_id);
+            continue;
+        }
+
+        let Some(checksum) = &beatmap.checksum else {
+            debug!("Beatmap {} has no checksum, skipping", beatmap.map_id);
+            continue;
+        };
+
+        let exists = BeatmapRow::exists_by_hash(pool, checksum)
+            .await
+            .map_err(|e| BeatmapWorkerError::DatabaseError(e.to_string()))?;
+        if exists {
+            debug!("Beatmap {} already processed, skipping", beatmap.map_id);
+            continue;
+        }
+
+        enqueue_hash(database, checksum).await?;
+        count += 1;
+    }
+
+    Ok(count)
+}
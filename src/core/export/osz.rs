@@ -0,0 +1,120 @@
+use crate::api::OsuApi;
+use crate::errors::ExportError;
+use crate::store;
+use crate::utils::rate::file_manager::FileManager;
+use crate::utils::rate::osz::OszBuilder;
+use db::db::DatabaseManager;
+use db::models::beatmaps::beatmap::BeatmapRow;
+use db::models::beatmaps::beatmapset::BeatmapsetRow;
+use rosu_map::Beatmap as RmBeatmap;
+use std::str::FromStr;
+use tracing::debug;
+
+/// Bundle the original difficulty and all its rates into a single `.osz` archive.
+///
+/// Only processed beatmaps are exported, others are `ExportError::NotFound`.
+pub async fn export_beatmap_osz(
+    database: &DatabaseManager,
+    osu_api: &dyn OsuApi,
+    beatmap_id: i32,
+) -> Result<Vec<u8>, ExportError> {
+    if BeatmapRow::find_by_osu_id(database.get_pool(), beatmap_id)
+        .await
+        .map_err(failed)?
+        .is_none()
+    {
+        return Err(ExportError::NotFound(format!("beatmap {}", beatmap_id)));
+    }
+
+    let mut builder = OszBuilder::new();
+    add_beatmap_rates(&mut builder, osu_api, beatmap_id).await?;
+    builder.finish().map_err(failed)
+}
+
+/// Bundle every processed difficulty of a beatmapset (by osu! id) and all their rates.
+pub async fn export_beatmapset_osz(
+    database: &DatabaseManager,
+    osu_api: &dyn OsuApi,
+    beatmapset_osu_id: i32,
+) -> Result<Vec<u8>, ExportError> {
+    let pool = database.get_pool();
+
+    let Some(beatmapset) = BeatmapsetRow::find_by_osu_id(pool, beatmapset_osu_id)
+        .await
+        .map_err(failed)?
+    else {
+        return Err(ExportError::NotFound(format!(
+            "beatmapset {}",
+            beatmapset_osu_id
+        )));
+    };
+
+    let beatmap_ids = store::beatmap::osu_ids_by_beatmapset_id(pool, beatmapset.id)
+        .await
+        .map_err(failed)?;
+
+    let mut builder = OszBuilder::new();
+    for beatmap_id in beatmap_ids {
+        add_beatmap_rates(&mut builder, osu_api, beatmap_id).await?;
+    }
+
+    builder.finish().map_err(failed)
+}
+
+async fn add_beatmap_rates(
+    builder: &mut OszBuilder,
+    osu_api: &dyn OsuApi,
+    beatmap_id: i32,
+) -> Result<(), ExportError> {
+    let files = load_local_files(beatmap_id.to_string()).await?;
+    // Original saved by the worker, the osu! API only for maps processed before that
+    let original = match files.original {
+        Some(original) => original,
+        None => osu_api.osu_file(beatmap_id as u32).await?,
+    };
+    let original_map = RmBeatmap::from_str(&original).map_err(failed)?;
+    builder
+        .add_beatmap(&original_map, original.as_bytes())
+        .map_err(failed)?;
+
+    debug!(
+        "Exporting beatmap {} with {} rate files",
+        beatmap_id,
+        files.rates.len()
+    );
+
+    for content in files.rates {
+        let map = RmBeatmap::from_str(&content).map_err(failed)?;
+
+        builder
+            .add_beatmap(&map, content.as_bytes())
+            .map_err(failed)?;
+    }
+
+    Ok(())
+}
+
+/// Files the worker stored for a beatmap
+struct LocalFiles {
+    original: Option<String>,
+    rates: Vec<String>,
+}
+
+/// Read the stored files of a beatmap on the blocking thread pool
+async fn load_local_files(storage_key: String) -> Result<LocalFiles, ExportError> {
+    tokio::task::spawn_blocking(move || {
+        let original = FileManager::load_original(&storage_key).map_err(failed)?;
+        let rates = FileManager::list_rate_files(&storage_key)
+            .map_err(failed)?
+            .iter()
+            .map(|path| FileManager::read_rate_file(path).map_err(failed))
+            .collect::<Result<_, _>>()?;
+        Ok(LocalFiles { original, rates })
+    })
+    .await
+    .map_err(failed)?
+}
+
+fn failed(error: impl ToString) -> ExportError {
+    ExportError::Failed(error.to_string())
+}
@@ -0,0 +1,159 @@
+use crate::store;
+use crate::utils::rate::file_manager::FileManager;
+use anyhow::{anyhow, Result};
+use db::db::DatabaseManager;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn};
+
+/// Summary of a garbage collection run
+#[derive(Debug, Default, Clone)]
+pub struct GcReport {
+    pub scanned_files: usize,
+    pub scanned_bytes: u64,
+    pub orphan_files: usize,
+    pub orphan_bytes: u64,
+    pub deduplicated_files: usize,
+    pub deduplicated_bytes: u64,
+}
+
+impl GcReport {
+    /// Bytes still used on disk after the run
+    pub fn remaining_bytes(&self) -> u64 {
+        self.scanned_bytes - self.orphan_bytes - self.deduplicated_bytes
+    }
+
+    pub fn log(&self, dry_run: bool) {
+        info!(
+            "GC{}: scanned {} files ({} bytes), orphans {} ({} bytes), deduplicated {} ({} bytes), remaining {} bytes",
+            if dry_run { " (dry run)" } else { "" },
+            self.scanned_files,
+            self.scanned_bytes,
+            self.orphan_files,
+            self.orphan_bytes,
+            self.deduplicated_files,
+            self.deduplicated_bytes,
+            self.remaining_bytes()
+        );
+    }
+}
+
+/// Delete rate files whose hash is no longer referenced by `RatesRow.osu_hash`
+/// and hard-link identical files (same content hash) kept under several beatmaps.
+///
+/// The worker writes rate files before inserting their rates, so unreferenced files
+/// modified less than `min_age` ago may belong to a beatmap being processed and are kept.
+pub async fn run_gc(
+    database: &DatabaseManager,
+    dry_run: bool,
+    min_age: Duration,
+) -> Result<GcReport> {
+    let pool = database.get_pool();
+    let known_hashes: HashSet<String> = store::rates::all_hashes(pool).await?.into_iter().collect();
+    debug!("GC: {} hashes referenced in database", known_hashes.len());
+
+    let files = FileManager::list_all_rate_files().map_err(|e| anyhow!(e.to_string()))?;
+    let mut report = GcReport::default();
+    // Grouped by file name (hash + codec extension): only identical content is linked
+    let mut by_name: HashMap<String, Vec<PathBuf>> = HashMap::new();
+
+    for (storage_key, path) in files {
+        let metadata = fs::metadata(&path).ok();
+        let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+        report.scanned_files += 1;
+        report.scanned_bytes += size;
+
+        let Some(hash) = FileManager::hash_from_path(&path) else {
+            continue;
+        };
+
+        if !known_hashes.contains(hash) {
+            if metadata.as_ref().is_some_and(|m| is_recent(m, min_age)) {
+                debug!("GC: keeping recent unreferenced file {}", path.display());
+                continue;
+            }
+            debug!(
+                "GC: orphan file for beatmap {}: {}",
+                storage_key,
+                path.display()
+            );
+            report.orphan_files += 1;
+            report.orphan_bytes += size;
+            if !dry_run {
+                if let Err(e) = fs::remove_file(&path) {
+                    warn!("GC: failed to delete {}: {}", path.display(), e);
+                }
+            }
+            continue;
+        }
+
+        let Some(name) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        by_name.entry(name).or_default().push(path);
+    }
+
+    for (name, paths) in by_name {
+        let Some((kept, duplicates)) = paths.split_first() else {
+            continue;
+        };
+
+        for duplicate in duplicates {
+            if FileManager::is_same_file(kept, duplicate) {
+                continue;
+            }
+
+            let size = fs::metadata(duplicate).map(|m| m.len()).unwrap_or(0);
+            debug!("GC: duplicate of {}: {}", name, duplicate.display());
+            report.deduplicated_files += 1;
+            report.deduplicated_bytes += size;
+            if !dry_run {
+                if let Err(e) = link_duplicate(kept, duplicate) {
+                    warn!("GC: failed to deduplicate {}: {}", duplicate.display(), e);
+                }
+            }
+        }
+    }
+
+    report.log(dry_run);
+    Ok(report)
+}
+
+/// Modified less than `min_age` ago, or at an unknown time
+fn is_recent(metadata: &fs::Metadata, min_age: Duration) -> bool {
+    let age = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    match age {
+        Some(age) => age < min_age,
+        None => true,
+    }
+}
+
+/// Replace `duplicate` by a hard link to `kept` without ever leaving it missing:
+/// the link is made under a temporary name in the same directory, then renamed over it.
+fn link_duplicate(kept: &Path, duplicate: &Path) -> io::Result<()> {
+    let file_name = duplicate
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid file name"))?;
+    // No codec extension, so never listed as a rate file if left behind by a crash
+    let temp = duplicate.with_file_name(format!(".{}.gc", file_name));
+
+    match fs::remove_file(&temp) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    fs::hard_link(kept, &temp)?;
+    fs::rename(&temp, duplicate).inspect_err(|_| {
+        let _ = fs::remove_file(&temp);
+    })
+}
@@ -0,0 +1,174 @@
+use crate::core::enqueue::enqueue_hash;
+use crate::core::worker::BeatmapWorker;
+use crate::utils::rate::hash::hash_md5;
+use anyhow::{anyhow, Result};
+use db::models::beatmaps::beatmap::BeatmapRow;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+/// What to do with beatmaps found on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Add their hash to the pending queue
+    Enqueue,
+    /// Process them right away from the local file content
+    Process,
+    /// Process them from the local file content only, without the osu! API
+    Offline,
+}
+
+/// A `.osu` file read from disk or from inside a `.osz` archive
+#[derive(Debug, Clone)]
+pub struct LocalBeatmap {
+    pub source: String,
+    pub osu_hash: String,
+    pub content: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ImportReport {
+    pub found: usize,
+    pub skipped: usize,
+    pub imported: usize,
+    pub failed: usize,
+}
+
+/// Import every `.osu`/`.osz` file under `path` (a file or a directory, walked recursively).
+pub async fn import_path(
+    worker: &BeatmapWorker,
+    path: &Path,
+    mode: ImportMode,
+) -> Result<ImportReport> {
+    let files = collect_files(path)?;
+    info!(
+        "Import: {} files found under {}",
+        files.len(),
+        path.display()
+    );
+
+    let pool = worker.config.database.get_pool();
+    let mut report = ImportReport::default();
+
+    for file in files {
+        let beatmaps = match read_local_beatmaps(&file) {
+            Ok(beatmaps) => beatmaps,
+            Err(e) => {
+                warn!("Import: failed to read {}: {}", file.display(), e);
+                report.failed += 1;
+                continue;
+            }
+        };
+
+        for beatmap in beatmaps {
+            report.found += 1;
+
+            if BeatmapRow::exists_by_hash(&pool, &beatmap.osu_hash).await? {
+                debug!("Import: {} already processed, skipping", beatmap.source);
+                report.skipped += 1;
+                continue;
+            }
+
+            let result = match mode {
+                ImportMode::Offline => worker
+                    .process_offline_beatmap(beatmap.content)
+                    .await
+                    .map_err(|e| anyhow!(e.to_string())),
+                ImportMode::Process => worker
+                    .process_local_beatmap(&beatmap.osu_hash, beatmap.content)
+                    .await
+                    .map_err(|e| anyhow!(e.to_string())),
+                ImportMode::Enqueue => enqueue_hash(&worker.config.database, &beatmap.osu_hash)
+                    .await
+                    .map_err(|e| anyhow!(e.to_string())),
+            };
+
+            match result {
+                Ok(()) => report.imported += 1,
+                Err(e) => {
+                    warn!("Import: {} failed: {}", beatmap.source, e);
+                    report.failed += 1;
+                }
+            }
+        }
+    }
+
+    info!(
+        "Import done: {} beatmaps found, {} imported, {} already processed, {} failed",
+        report.found, report.imported, report.skipped, report.failed
+    );
+    Ok(report)
+}
+
+/// List `.osu` and `.osz` files under a path
+pub fn collect_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    if path.is_file() {
+        if is_importable(path) {
+            files.push(path.to_path_buf());
+        }
+        return Ok(files);
+    }
+
+    let mut dirs = vec![path.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if is_importable(&path) {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Read the beatmaps of a `.osu` file or of every `.osu` inside a `.osz` archive
+pub fn read_local_beatmaps(path: &Path) -> Result<Vec<LocalBeatmap>> {
+    let source = path.display().to_string();
+
+    if has_extension(path, "osu") {
+        let content = std::fs::read_to_string(path)?;
+        return Ok(vec![local_beatmap(source, content)?]);
+    }
+
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+    let mut beatmaps = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if !entry.name().to_ascii_lowercase().ends_with(".osu") {
+            continue;
+        }
+
+        let entry_source = format!("{}:{}", source, entry.name());
+        let mut content = String::new();
+        entry.read_to_string(&mut content)?;
+        beatmaps.push(local_beatmap(entry_source, content)?);
+    }
+
+    Ok(beatmaps)
+}
+
+fn local_beatmap(source: String, content: String) -> Result<LocalBeatmap> {
+    let osu_hash = hash_md5(&content).map_err(|e| anyhow!(e))?;
+    Ok(LocalBeatmap {
+        source,
+        osu_hash,
+        content,
+    })
+}
+
+fn is_importable(path: &Path) -> bool {
+    has_extension(path, "osu") || has_extension(path, "osz")
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+}
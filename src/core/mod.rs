@@ -1,4 +1,15 @@
 pub mod beatmap;
 pub mod beatmapset;
+pub mod discovery;
+pub mod enqueue;
+pub mod export;
+pub mod gc;
+pub mod import;
+pub mod notify;
+pub mod query;
 pub mod rating;
+pub mod refresh;
+pub mod shutdown;
+pub mod startup;
+pub mod status;
 pub mod worker;
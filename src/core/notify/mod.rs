@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+use dto::models::beatmaps::full::types::Beatmapset;
+
+/// Receives processing results, e.g. to post them on Discord
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Called once a beatmapset and its rates have been inserted
+    async fn beatmap_processed(&self, beatmapset: &Beatmapset);
+}
+
+/// Notifier used when no integration is configured
+pub struct NoopNotifier;
+
+#[async_trait]
+impl Notifier for NoopNotifier {
+    async fn beatmap_processed(&self, _beatmapset: &Beatmapset) {}
+}
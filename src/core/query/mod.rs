@@ -0,0 +1,74 @@
+pub mod recommend;
+pub mod search;
+
+use crate::core::beatmap::from::beatmap_from_row;
+use crate::core::beatmapset::from::beatmapset_from_row;
+use crate::core::rating::from_row::{rates_from_row, rating_from_row};
+use crate::store;
+use anyhow::Result;
+use db::db::DatabaseManager;
+use db::models::beatmaps::beatmap::BeatmapRow;
+use db::models::beatmaps::rates::RatesRow;
+use dto::models::beatmaps::full::types::Beatmapset;
+use dto::models::rate::Rates;
+
+/// Processed beatmap by osu! id, inside its beatmapset, with all rates and ratings
+pub async fn beatmap_by_osu_id(
+    database: &DatabaseManager,
+    osu_id: i32,
+) -> Result<Option<Beatmapset>> {
+    let pool = database.get_pool();
+
+    match BeatmapRow::find_by_osu_id(pool, osu_id).await? {
+        Some(beatmap) => full_beatmap(database, &beatmap).await,
+        None => Ok(None),
+    }
+}
+
+/// Processed beatmap owning the rate file with this hash
+pub async fn beatmap_by_hash(database: &DatabaseManager, hash: &str) -> Result<Option<Beatmapset>> {
+    let pool = database.get_pool();
+
+    let Some(rates) = store::rates::find_by_osu_hash(pool, hash).await? else {
+        return Ok(None);
+    };
+    match store::beatmap::find_by_id(pool, rates.beatmap_id).await? {
+        Some(beatmap) => full_beatmap(database, &beatmap).await,
+        None => Ok(None),
+    }
+}
+
+async fn full_beatmap(
+    database: &DatabaseManager,
+    beatmap: &BeatmapRow,
+) -> Result<Option<Beatmapset>> {
+    let pool = database.get_pool();
+
+    let Some(beatmapset_id) = beatmap.beatmapset_id else {
+        return Ok(None);
+    };
+    let Some(beatmapset) = store::beatmapset::find_by_id(pool, beatmapset_id).await? else {
+        return Ok(None);
+    };
+
+    let mut rates = Vec::new();
+    for rates_row in store::rates::find_by_beatmap_id(pool, beatmap.id).await? {
+        rates.push(full_rates(database, &rates_row).await?);
+    }
+    rates.sort_by_key(|r| r.centirate);
+
+    let beatmap = beatmap_from_row(beatmap, rates);
+    Ok(Some(beatmapset_from_row(&beatmapset, vec![beatmap])))
+}
+
+async fn full_rates(database: &DatabaseManager, rates: &RatesRow) -> Result<Rates> {
+    let pool = database.get_pool();
+
+    let mut ratings = Vec::new();
+    for rating in store::rating::find_by_rates_id(pool, rates.id).await? {
+        let mania = store::rating::mania_by_rating_id(pool, rating.id).await?;
+        ratings.push(rating_from_row(&rating, mania.as_ref()));
+    }
+
+    Ok(rates_from_row(rates, ratings))
+}
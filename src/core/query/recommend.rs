@@ -0,0 +1,239 @@
+use crate::core::query::search::SearchHit;
+use crate::core::rating::skillset::Skillset;
+use crate::errors::QueryError;
+use crate::store;
+use db::db::DatabaseManager;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_LIMIT: u32 = 20;
+pub const MAX_LIMIT: u32 = 100;
+/// Recent maps averaged into the target, more would only slow the query down
+pub const MAX_RECENT_HASHES: usize = 100;
+
+/// One value per skillset, used both as a target and as weights
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SkillsetVector {
+    pub stream: f64,
+    pub jumpstream: f64,
+    pub handstream: f64,
+    pub stamina: f64,
+    pub jackspeed: f64,
+    pub chordjack: f64,
+    pub technical: f64,
+}
+
+impl SkillsetVector {
+    pub const fn splat(value: f64) -> Self {
+        Self {
+            stream: value,
+            jumpstream: value,
+            handstream: value,
+            stamina: value,
+            jackspeed: value,
+            chordjack: value,
+            technical: value,
+        }
+    }
+
+    pub fn get(&self, skillset: Skillset) -> f64 {
+        match skillset {
+            Skillset::Stream => self.stream,
+            Skillset::Jumpstream => self.jumpstream,
+            Skillset::Handstream => self.handstream,
+            Skillset::Stamina => self.stamina,
+            Skillset::Jackspeed => self.jackspeed,
+            Skillset::Chordjack => self.chordjack,
+            Skillset::Technical => self.technical,
+        }
+    }
+
+    pub fn set(&mut self, skillset: Skillset, value: f64) {
+        let field = match skillset {
+            Skillset::Stream => &mut self.stream,
+            Skillset::Jumpstream => &mut self.jumpstream,
+            Skillset::Handstream => &mut self.handstream,
+            Skillset::Stamina => &mut self.stamina,
+            Skillset::Jackspeed => &mut self.jackspeed,
+            Skillset::Chordjack => &mut self.chordjack,
+            Skillset::Technical => &mut self.technical,
+        };
+        *field = value;
+    }
+}
+
+impl Default for SkillsetVector {
+    fn default() -> Self {
+        Self::splat(1.0)
+    }
+}
+
+/// Either a target skillset vector or the rate hashes of a player's recent maps,
+/// whose skillsets are averaged into the target
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecommendRequest {
+    #[serde(default = "default_rating_type")]
+    pub rating_type: String,
+    #[serde(default)]
+    pub target: Option<SkillsetVector>,
+    #[serde(default)]
+    pub recent_hashes: Vec<String>,
+    #[serde(default)]
+    pub weights: SkillsetVector,
+    #[serde(default)]
+    pub keys: Option<i32>,
+    /// Element of `main_pattern` every result must have, e.g. `jumpstream` or `LN`
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+impl RecommendRequest {
+    pub fn limit(&self) -> u32 {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+
+    /// Reject requests the query cannot rank, the message is meant for the client
+    pub fn validate(&self) -> Result<(), String> {
+        if self.target.is_none() && self.recent_hashes.is_empty() {
+            return Err("either target or recent_hashes is required".to_string());
+        }
+        if self.recent_hashes.len() > MAX_RECENT_HASHES {
+            return Err(format!(
+                "at most {} recent_hashes are accepted",
+                MAX_RECENT_HASHES
+            ));
+        }
+        if let Some(target) = &self.target {
+            if Skillset::ALL.iter().any(|s| !target.get(*s).is_finite()) {
+                return Err("target skillsets must be finite numbers".to_string());
+            }
+        }
+
+        let weights = Skillset::ALL.map(|skillset| self.weights.get(skillset));
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err("weights must be finite and not negative".to_string());
+        }
+        // All-zero weights put every map at distance 0, the order would be arbitrary
+        if !weights.iter().any(|w| *w > 0.0) {
+            return Err("at least one weight must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn default_rating_type() -> String {
+    "etterna".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Recommendation {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub hit: SearchHit,
+    /// Weighted euclidean distance to the target, lower is closer
+    pub distance: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Recommendations {
+    pub target: SkillsetVector,
+    pub results: Vec<Recommendation>,
+}
+
+/// Nearest beatmap rates to the target by weighted distance over the seven skillsets.
+pub async fn recommend(
+    database: &DatabaseManager,
+    request: &RecommendRequest,
+) -> Result<Recommendations, QueryError> {
+    request.validate().map_err(QueryError::InvalidRequest)?;
+
+    let pool = database.get_pool();
+    let target = match request.target {
+        Some(target) => target,
+        None => {
+            store::recommend::average_skillsets(pool, &request.rating_type, &request.recent_hashes)
+                .await?
+                .ok_or_else(|| {
+                    QueryError::NotFound("none of the given maps has been processed".to_string())
+                })?
+        }
+    };
+
+    let results = store::recommend::nearest(pool, request, &target).await?;
+    Ok(Recommendations { target, results })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(weights: SkillsetVector) -> RecommendRequest {
+        RecommendRequest {
+            rating_type: default_rating_type(),
+            target: Some(SkillsetVector::splat(20.0)),
+            recent_hashes: Vec::new(),
+            weights,
+            keys: None,
+            pattern: None,
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn default_weights_are_valid() {
+        assert_eq!(request(SkillsetVector::default()).validate(), Ok(()));
+    }
+
+    #[test]
+    fn a_single_positive_weight_is_enough() {
+        let mut weights = SkillsetVector::splat(0.0);
+        weights.technical = 0.5;
+        assert_eq!(request(weights).validate(), Ok(()));
+    }
+
+    #[test]
+    fn all_zero_weights_are_rejected() {
+        assert!(request(SkillsetVector::splat(0.0)).validate().is_err());
+    }
+
+    #[test]
+    fn negative_or_non_finite_weights_are_rejected() {
+        for value in [-1.0, f64::NAN, f64::INFINITY] {
+            let mut weights = SkillsetVector::default();
+            weights.stamina = value;
+            assert!(request(weights).validate().is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn non_finite_target_is_rejected() {
+        let mut request = request(SkillsetVector::default());
+        request.target = Some(SkillsetVector::splat(f64::NAN));
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn target_or_recent_hashes_is_required() {
+        let mut request = request(SkillsetVector::default());
+        request.target = None;
+        assert!(request.validate().is_err());
+
+        request.recent_hashes = vec!["0123456789abcdef0123456789abcdef".to_string()];
+        assert_eq!(request.validate(), Ok(()));
+    }
+
+    #[test]
+    fn too_many_recent_hashes_are_rejected() {
+        let mut request = request(SkillsetVector::default());
+        request.target = None;
+        request.recent_hashes =
+            vec!["0123456789abcdef0123456789abcdef".to_string(); MAX_RECENT_HASHES];
+        assert_eq!(request.validate(), Ok(()));
+
+        request
+            .recent_hashes
+            .push("fedcba9876543210fedcba9876543210".to_string());
+        assert!(request.validate().is_err());
+    }
+}
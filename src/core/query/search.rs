@@ -0,0 +1,134 @@
+use crate::core::rating::skillset::Skillset;
+use crate::store;
+use anyhow::Result;
+use db::db::DatabaseManager;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PER_PAGE: u32 = 50;
+pub const MAX_PER_PAGE: u32 = 100;
+
+/// Sort key of a search, ties are always broken by rate id for stable pages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSort {
+    #[default]
+    Rating,
+    /// Value of the filtered skillset, falls back to the rating without one
+    Skillset,
+    Bpm,
+    DrainTime,
+    Centirate,
+    /// Most recently processed first when descending
+    Id,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Filters over processed beatmap rates, every field is optional
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BeatmapSearch {
+    /// `etterna`, `osu` or `sunnyxxy`; one result per rate and rating type
+    pub rating_type: Option<String>,
+    pub min_rating: Option<f64>,
+    pub max_rating: Option<f64>,
+    pub skillset: Option<Skillset>,
+    pub min_skillset: Option<f64>,
+    pub max_skillset: Option<f64>,
+    /// Element of `main_pattern`, e.g. `jumpstream` or `LN`
+    pub pattern: Option<String>,
+    /// Key count (CS of mania beatmaps)
+    pub keys: Option<i32>,
+    pub status: Option<String>,
+    /// Rate such as `1.2`
+    pub rate: Option<f64>,
+    pub sort: SearchSort,
+    pub order: SortOrder,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+impl BeatmapSearch {
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> u32 {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+
+    /// Reject filters the query cannot use, the message is meant for the client
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(rate) = self.rate {
+            if !rate.is_finite() || rate <= 0.0 {
+                return Err(format!("invalid rate {}", rate));
+            }
+        }
+        let bounds = [
+            ("min_rating", self.min_rating),
+            ("max_rating", self.max_rating),
+            ("min_skillset", self.min_skillset),
+            ("max_skillset", self.max_skillset),
+        ];
+        for (name, value) in bounds {
+            if value.is_some_and(|value| !value.is_finite()) {
+                return Err(format!("{} must be a finite number", name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One beatmap rate with one of its ratings
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SearchHit {
+    pub beatmap_osu_id: Option<i32>,
+    pub beatmapset_osu_id: Option<i32>,
+    pub artist: String,
+    pub title: String,
+    pub creator: String,
+    pub difficulty: String,
+    pub status: String,
+    pub main_pattern: serde_json::Value,
+    pub osu_hash: String,
+    pub centirate: i32,
+    pub bpm: f64,
+    pub drain_time: i32,
+    pub rating_type: String,
+    pub rating: f64,
+    pub stream: Option<f64>,
+    pub jumpstream: Option<f64>,
+    pub handstream: Option<f64>,
+    pub stamina: Option<f64>,
+    pub jackspeed: Option<f64>,
+    pub chordjack: Option<f64>,
+    pub technical: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchPage {
+    pub page: u32,
+    pub per_page: u32,
+    pub results: Vec<SearchHit>,
+}
+
+pub async fn search_beatmaps(
+    database: &DatabaseManager,
+    search: &BeatmapSearch,
+) -> Result<SearchPage> {
+    let results = store::search::search(database.get_pool(), search).await?;
+
+    Ok(SearchPage {
+        page: search.page(),
+        per_page: search.per_page(),
+        results,
+    })
+}
@@ -2,13 +2,15 @@ use crate::core::rating::make_rates::RatesMaker;
 use crate::core::rating::proportion::Proportion;
 use crate::utils::calculator::get_star_rating;
 use crate::utils::calculator::get_sunnyxxy_rating;
+use crate::utils::calculator::CalculatorOptions;
 use anyhow::Result;
 use dto::models::rate::{ManiaRating, ModeRating, Rates, Rating};
 use tracing::debug;
 
-pub async fn rates_from_skillset_scores(
+pub fn rates_from_skillset_scores(
     make_rates: &mut RatesMaker,
     hash: String,
+    calculators: &CalculatorOptions,
 ) -> Result<Rates> {
 
 
@@ -16,7 +18,7 @@ pub async fn rates_from_skillset_scores(
     let proportions = calculate_proportions(make_rates);
     let osu_hash = hash;
 
-    let ratings = create_all_ratings(make_rates, &proportions, &make_rates.osu_map);
+    let ratings = create_all_ratings(make_rates, &proportions, &make_rates.osu_map, calculators);
 
     let rates = Rates {
         id: None,
@@ -88,12 +90,18 @@ fn create_all_ratings(
     make_rates: &RatesMaker,
     proportions: &Proportion,
     osu_map: &str,
+    calculators: &CalculatorOptions,
 ) -> Vec<Rating> {
-    let etterna_rating = create_etterna_rating(make_rates);
-    let osu_rating = create_osu_rating(osu_map, proportions, make_rates.centirate as i64);
-    let sunny_rating = create_sunny_rating(osu_map, proportions, make_rates.centirate as i64);
+    let centirate = make_rates.centirate as i64;
+    let mut ratings = vec![create_etterna_rating(make_rates)];
+    if calculators.sunnyxxy {
+        ratings.push(create_sunny_rating(osu_map, proportions, centirate));
+    }
+    if calculators.osu {
+        ratings.push(create_osu_rating(osu_map, proportions, centirate));
+    }
 
-    vec![etterna_rating, sunny_rating, osu_rating]
+    ratings
 }
 
 fn create_etterna_rating(make_rates: &RatesMaker) -> Rating {
@@ -0,0 +1,38 @@
+use bigdecimal::{BigDecimal, ToPrimitive};
+use db::models::beatmaps::rates::RatesRow;
+use db::models::rating::beatmap_mania_rating::BeatmapManiaRatingRow;
+use db::models::rating::beatmap_rating::BeatmapRatingRow;
+use dto::models::rate::{ManiaRating, ModeRating, Rates, Rating};
+
+pub fn rates_from_row(row: &RatesRow, rating: Vec<Rating>) -> Rates {
+    Rates {
+        id: Some(row.id),
+        osu_hash: Some(row.osu_hash.clone()),
+        centirate: row.centirate,
+        drain_time: row.drain_time,
+        total_time: row.total_time,
+        bpm: row.bpm.to_f32().unwrap_or_default(),
+        rating,
+    }
+}
+
+pub fn rating_from_row(row: &BeatmapRatingRow, mania: Option<&BeatmapManiaRatingRow>) -> Rating {
+    let skillset = |value: Option<&BigDecimal>| value.and_then(ToPrimitive::to_f64).unwrap_or(0.0);
+
+    Rating {
+        id: Some(row.id),
+        rates_id: row.rates_id,
+        rating: row.rating.to_f64().unwrap_or_default(),
+        rating_type: row.rating_type.clone(),
+        mode_rating: ModeRating::Mania(ManiaRating {
+            id: mania.map(|m| m.id),
+            stream: skillset(mania.and_then(|m| m.stream.as_ref())),
+            jumpstream: skillset(mania.and_then(|m| m.jumpstream.as_ref())),
+            handstream: skillset(mania.and_then(|m| m.handstream.as_ref())),
+            stamina: skillset(mania.and_then(|m| m.stamina.as_ref())),
+            jackspeed: skillset(mania.and_then(|m| m.jackspeed.as_ref())),
+            chordjack: skillset(mania.and_then(|m| m.chordjack.as_ref())),
+            technical: skillset(mania.and_then(|m| m.technical.as_ref())),
+        }),
+    }
+}
@@ -0,0 +1,53 @@
+use crate::core::beatmap::timings::BeatmapTimings;
+use crate::core::rating::rated::RatedRates;
+use crate::core::worker::process::rate_beatmap;
+use crate::errors::BeatmapWorkerError;
+use crate::utils::rate::options::RateOptions;
+use minacalc_rs::Calc;
+use rosu_map::section::general::GameMode;
+use rosu_map::Beatmap as RmBeatmap;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Ratings computed for one `.osu` file, one `RatedRates` per configured rate
+#[derive(Debug, Clone, Serialize)]
+pub struct BeatmapRating {
+    pub main_pattern: serde_json::Value,
+    pub rates: Vec<RatedRates>,
+}
+
+/// Rate a `.osu` file without database, osu! API or rate files written to disk.
+///
+/// Runs minacalc, rosu-pp and ssrrr for every rate of `options.centirates`.
+pub fn rate_osu_file(
+    osu_map: &str,
+    calc: &Calc,
+    options: &RateOptions,
+) -> Result<BeatmapRating, BeatmapWorkerError> {
+    let parsed = RmBeatmap::from_str(osu_map)
+        .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?;
+    if parsed.mode != GameMode::Mania || parsed.circle_size != 4.0 {
+        return Err(BeatmapWorkerError::ProcessingFailed(
+            "only 4K osu!mania beatmaps can be rated".to_string(),
+        ));
+    }
+
+    let timings = BeatmapTimings::from_rosu_map(&parsed);
+
+    rate_beatmap(&timings, calc, osu_map, options, None)
+}
+
+/// Same as `rate_osu_file` on the blocking thread pool, with its own calculator,
+/// for callers running on the async runtime.
+pub async fn rate_osu_file_blocking(
+    osu_map: String,
+    options: RateOptions,
+) -> Result<BeatmapRating, BeatmapWorkerError> {
+    tokio::task::spawn_blocking(move || {
+        let calc = Calc::new()
+            .map_err(|e| BeatmapWorkerError::InitializationFailed(format!("{:?}", e)))?;
+        rate_osu_file(&osu_map, &calc, &options)
+    })
+    .await
+    .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?
+}
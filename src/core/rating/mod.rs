@@ -1,3 +1,7 @@
 pub mod from;
+pub mod from_row;
+pub mod local;
 pub mod make_rates;
 pub mod proportion;
+pub mod rated;
+pub mod skillset;
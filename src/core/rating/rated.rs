@@ -0,0 +1,25 @@
+use dto::models::rate::Rates;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// OD/HP of a rated file once `DifficultyOptions` are applied
+///
+/// The dto `Rates` has no such fields, they are stored in the `rates.od` and `rates.hp`
+/// columns added by `migrations/0001_rates_difficulty.sql`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RateDifficulty {
+    pub od: f64,
+    pub hp: f64,
+}
+
+/// Difficulty of each rated file, by `osu_hash`
+pub type RateDifficulties = HashMap<String, RateDifficulty>;
+
+/// `Rates` of one rated file with its difficulty
+#[derive(Debug, Clone, Serialize)]
+pub struct RatedRates {
+    #[serde(flatten)]
+    pub rates: Rates,
+    #[serde(flatten)]
+    pub difficulty: RateDifficulty,
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Skillsets stored in `ManiaRating`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Skillset {
+    Stream,
+    Jumpstream,
+    Handstream,
+    Stamina,
+    Jackspeed,
+    Chordjack,
+    Technical,
+}
+
+impl Skillset {
+    pub const ALL: [Skillset; 7] = [
+        Skillset::Stream,
+        Skillset::Jumpstream,
+        Skillset::Handstream,
+        Skillset::Stamina,
+        Skillset::Jackspeed,
+        Skillset::Chordjack,
+        Skillset::Technical,
+    ];
+
+    /// Column name in the mania rating table
+    pub fn column(self) -> &'static str {
+        match self {
+            Skillset::Stream => "stream",
+            Skillset::Jumpstream => "jumpstream",
+            Skillset::Handstream => "handstream",
+            Skillset::Stamina => "stamina",
+            Skillset::Jackspeed => "jackspeed",
+            Skillset::Chordjack => "chordjack",
+            Skillset::Technical => "technical",
+        }
+    }
+}
+
+impl FromStr for Skillset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Skillset::ALL
+            .into_iter()
+            .find(|skillset| skillset.column().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown skillset: {}", s))
+    }
+}
+
+impl fmt::Display for Skillset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.column())
+    }
+}
@@ -0,0 +1,124 @@
+use crate::api::OsuApi;
+use crate::core::shutdown::Shutdown;
+use crate::errors::OsuApiError;
+use crate::store;
+use crate::utils::rank_status_to_string;
+use chrono::{DateTime, Utc};
+use db::db::DatabaseManager;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Settings of the periodic rank status refresh
+#[derive(Debug, Clone)]
+pub struct RefreshOptions {
+    pub enabled: bool,
+    pub interval: Duration,
+    /// Statuses that may still change and are re-checked on each run
+    pub statuses: Vec<String>,
+}
+
+impl Default for RefreshOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(6 * 3600),
+            statuses: ["pending", "wip", "qualified", "graveyard"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Re-checks the rank status of processed beatmaps whose status is not final,
+/// updating their beatmap and beatmapset rows without recomputing ratings
+pub struct StatusRefreshTask {
+    pub database: DatabaseManager,
+    pub osu_api: Arc<dyn OsuApi>,
+    pub options: RefreshOptions,
+}
+
+impl StatusRefreshTask {
+    /// Runs a pass every `interval` until a shutdown is requested, which also cuts the
+    /// current pass short
+    pub async fn run(&self, shutdown: Shutdown) {
+        info!(
+            "Status refresh task started, interval {}s",
+            self.options.interval.as_secs()
+        );
+
+        while !shutdown.is_requested() {
+            tokio::select! {
+                result = self.run_once() => {
+                    if let Err(e) = result {
+                        error!("Status refresh failed: {}", e);
+                    }
+                }
+                _ = shutdown.requested() => break,
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(self.options.interval) => {}
+                _ = shutdown.requested() => {}
+            }
+        }
+
+        info!("Status refresh task stopped");
+    }
+
+    /// Run a single refresh pass, returns the number of beatmaps whose status changed
+    pub async fn run_once(&self) -> anyhow::Result<usize> {
+        let pool = self.database.get_pool();
+        let beatmaps = store::beatmap::find_by_statuses(pool, &self.options.statuses).await?;
+        debug!("Refreshing status of {} beatmaps", beatmaps.len());
+
+        let mut updated = 0;
+        for beatmap_row in beatmaps {
+            let Some(osu_id) = beatmap_row.osu_id else {
+                continue;
+            };
+
+            // The cache keeps a non-final status for a while, it would hide the change
+            let beatmap = match self.osu_api.fresh_beatmap_by_osu_id(osu_id).await {
+                Ok(beatmap) => beatmap,
+                Err(OsuApiError::NotFound(_)) => {
+                    warn!("Beatmap {} no longer exists on osu!", osu_id);
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Failed to refresh beatmap {}: {}", osu_id, e);
+                    continue;
+                }
+            };
+
+            let status = rank_status_to_string(&beatmap.status);
+            if status == beatmap_row.status {
+                continue;
+            }
+
+            info!(
+                "Beatmap {} status changed: {} -> {}",
+                osu_id, beatmap_row.status, status
+            );
+            store::beatmap::update_status(pool, beatmap_row.id, &status).await?;
+
+            if let (Some(beatmapset_id), Some(mapset)) =
+                (beatmap_row.beatmapset_id, &beatmap.mapset)
+            {
+                // `last_updated` moves with every edit, `ranked_date` only when the status does
+                let changed_at = mapset
+                    .ranked_date
+                    .and_then(|date| DateTime::from_timestamp(date.unix_timestamp(), 0))
+                    .unwrap_or_else(Utc::now)
+                    .naive_utc();
+                store::beatmapset::update_status_changed_at(pool, beatmapset_id, changed_at)
+                    .await?;
+            }
+
+            updated += 1;
+        }
+
+        info!("Status refresh done: {} beatmaps updated", updated);
+        Ok(updated)
+    }
+}
@@ -0,0 +1,73 @@
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+/// Shutdown request shared by the long-running tasks
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    receiver: watch::Receiver<bool>,
+}
+
+impl Shutdown {
+    /// Listen for SIGINT and SIGTERM in the background
+    ///
+    /// Installing the handler replaces the default behaviour of both signals, so this
+    /// is only called once the process is ready to stop on its own.
+    pub fn listen() -> Self {
+        let (sender, shutdown) = Self::manual();
+        tokio::spawn(async move {
+            let signal = wait_for_signal().await;
+            info!("Received {}, shutting down", signal);
+            let _ = sender.send(true);
+        });
+        shutdown
+    }
+
+    /// Shutdown requested by sending `true` on the returned sender, e.g. from tests
+    pub fn manual() -> (watch::Sender<bool>, Self) {
+        let (sender, receiver) = watch::channel(false);
+        (sender, Self { receiver })
+    }
+
+    pub fn is_requested(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    /// Resolves once a shutdown has been requested
+    pub async fn requested(&self) {
+        let mut receiver = self.receiver.clone();
+        loop {
+            if *receiver.borrow_and_update() {
+                return;
+            }
+            if receiver.changed().await.is_err() {
+                // Listener gone without a signal: no shutdown will ever be requested
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> &'static str {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => {
+            warn!("Failed to listen for SIGTERM: {}", e);
+            let _ = tokio::signal::ctrl_c().await;
+            return "SIGINT";
+        }
+    };
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => "SIGINT",
+        _ = terminate.recv() => "SIGTERM",
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> &'static str {
+    let _ = tokio::signal::ctrl_c().await;
+    "Ctrl-C"
+}
@@ -0,0 +1,70 @@
+use crate::errors::StartupError;
+use crate::store;
+use db::config::DatabaseConfig;
+use db::db::DatabaseManager;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Connection to Postgres at startup, retried so the worker can start before the database
+#[derive(Debug, Clone)]
+pub struct DatabaseOptions {
+    /// Replaces the URL the db crate reads from `DATABASE_URL` (from the config file or `--set`)
+    pub url: Option<String>,
+    /// 0 retries forever
+    pub connect_max_attempts: u32,
+    /// First delay of the exponential backoff between attempts
+    pub connect_initial_backoff: Duration,
+    pub connect_max_backoff: Duration,
+}
+
+impl Default for DatabaseOptions {
+    fn default() -> Self {
+        Self {
+            url: None,
+            connect_max_attempts: 0,
+            connect_initial_backoff: Duration::from_secs(1),
+            connect_max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Connect the database, waiting with an exponential backoff while it is unreachable,
+/// then apply the schema changes of `migrations/`
+pub async fn connect_database(
+    database: &mut DatabaseManager,
+    options: &DatabaseOptions,
+) -> Result<(), StartupError> {
+    let mut database_config = DatabaseConfig::load();
+    if let Some(url) = &options.url {
+        database_config.database_url = url.clone();
+    }
+
+    let mut backoff = options.connect_initial_backoff;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let error = match database.connect(&database_config).await {
+            Ok(_) => {
+                info!("Connected to the database after {} attempt(s)", attempt);
+                return store::migrate(database.get_pool())
+                    .await
+                    .map_err(|e| StartupError::Migration(e.to_string()));
+            }
+            Err(e) => e.to_string(),
+        };
+
+        if options.connect_max_attempts != 0 && attempt >= options.connect_max_attempts {
+            return Err(StartupError::DatabaseUnavailable {
+                attempts: attempt,
+                reason: error,
+            });
+        }
+
+        warn!(
+            "Database unreachable (attempt {}): {}, retrying in {:?}",
+            attempt, error, backoff
+        );
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(options.connect_max_backoff);
+    }
+}
@@ -0,0 +1,23 @@
+use crate::store;
+use anyhow::Result;
+use db::db::DatabaseManager;
+
+/// Snapshot of the processing queue and of what has been processed so far
+#[derive(Debug, Default, Clone)]
+pub struct QueueStatus {
+    pub pending: i64,
+    pub failed: i64,
+    pub beatmaps: i64,
+    pub rates: i64,
+}
+
+pub async fn queue_status(database: &DatabaseManager) -> Result<QueueStatus> {
+    let pool = database.get_pool();
+
+    Ok(QueueStatus {
+        pending: store::pending::count(pool).await?,
+        failed: store::failed_query::count(pool).await?,
+        beatmaps: store::beatmap::count(pool).await?,
+        rates: store::rates::count(pool).await?,
+    })
+}
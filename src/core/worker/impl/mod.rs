@@ -1,3 +1,5 @@
 pub mod insert;
 pub mod process;
+pub mod recalc;
 pub mod start;
+pub mod wakeup;
@@ -1,37 +1,83 @@
+use crate::core::beatmap::timings::BeatmapTimings;
 use crate::core::rating::from::rates_from_skillset_scores;
+use crate::core::rating::local::BeatmapRating;
 use crate::core::rating::make_rates::RatesMaker;
+use crate::core::rating::rated::{RateDifficulties, RateDifficulty, RatedRates};
 use crate::errors::BeatmapWorkerError;
 use crate::utils::determine_main_pattern;
-use crate::utils::osu_file_from_url;
-// use crate::utils::rate::file_manager::FileManager;
+use crate::utils::rate::file_manager::FileManager;
 use dto::models::beatmaps::full::types::Beatmap;
 use minacalc_rs::{hashmap::HashMapCalcExt, osu::OsuCalcExt, Calc};
 use rosu_map::Beatmap as RmBeatmap;
-use rosu_v2::prelude::BeatmapExtended;
 use std::str::FromStr;
 use std::time::Instant;
 use tracing::{debug, info, warn};
-use crate::utils::rate::rate::process_single_rate;
+use crate::utils::rate::options::RateOptions;
+use crate::utils::rate::rate::{process_single_rate, render_rate};
 
+/// Calcule les rates sur le pool de threads bloquants, avec un calculateur dédié,
+/// puis les ajoute à `beatmap_row`
 pub(crate) async fn process_beatmap(
-    beatmap: &BeatmapExtended,
-    calc: &Calc,
-    osu_path: String,
+    timings: &BeatmapTimings,
+    osu_map: String,
     beatmap_row: &mut Beatmap,
-) -> Result<(), BeatmapWorkerError> {
+    rate_options: &RateOptions,
+) -> Result<RateDifficulties, BeatmapWorkerError> {
     let start_all = Instant::now();
-    debug!("Starting beatmap processing for osu_id: {}", beatmap.map_id);
+    // Les maps hors ligne sans osu_id sont rangées sous le hash de leur `.osu`
+    let storage_key = FileManager::storage_key(beatmap_row.osu_id, &osu_map)
+        .map_err(BeatmapWorkerError::ProcessingFailed)?;
+    debug!("Starting beatmap processing for {}", storage_key);
+
+    info!("Osu file length: {} bytes", osu_map.len());
+
+    let timings = *timings;
+    let rate_options = rate_options.clone();
+    let rating = tokio::task::spawn_blocking(move || {
+        let calc = Calc::new()
+            .map_err(|e| BeatmapWorkerError::InitializationFailed(format!("{:?}", e)))?;
+        let rating = rate_beatmap(&timings, &calc, &osu_map, &rate_options, Some(&storage_key))?;
+        // Gardé pour les exports `.osz`, sans repasser par l'API osu!
+        FileManager::save_original(&storage_key, &osu_map, &rate_options.compression)
+            .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?;
+        Ok::<_, BeatmapWorkerError>(rating)
+    })
+    .await
+    .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))??;
+
+    beatmap_row.main_pattern = rating.main_pattern;
+    let mut difficulties = RateDifficulties::new();
+    for rated in rating.rates {
+        if let Some(osu_hash) = &rated.rates.osu_hash {
+            difficulties.insert(osu_hash.clone(), rated.difficulty);
+        }
+        beatmap_row.rates.push(rated.rates);
+    }
 
-    debug!("Fetching osu file from URL: {}", osu_path);
-    let osu_map = osu_file_from_url(&osu_path).await.unwrap();
-    info!("Osu file fetched, length: {} bytes", osu_map.len());
+    let elapsed = start_all.elapsed();
+    info!(
+        "process_beatmap done: osu_id={}, elapsed_ms={}",
+        beatmap_row.osu_id.unwrap_or_default(),
+        elapsed.as_millis()
+    );
+    Ok(difficulties)
+}
 
-    let parsed_beatmap = RmBeatmap::from_str(&osu_map).unwrap();
+/// Calcule le pattern principal et les `Rates` de chaque rate configurée.
+///
+/// Les fichiers de rate ne sont sauvegardés que si `storage_key` est fourni.
+pub(crate) fn rate_beatmap(
+    timings: &BeatmapTimings,
+    calc: &Calc,
+    osu_map: &str,
+    rate_options: &RateOptions,
+    storage_key: Option<&str>,
+) -> Result<BeatmapRating, BeatmapWorkerError> {
+    let parsed_beatmap = RmBeatmap::from_str(osu_map)
+        .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?;
     debug!("Beatmap parsed successfully");
 
-    let rates_centirate = vec![
-        70, 80, 90, 100, 110, 120, 130, 140, 150, 160, 170, 180, 190, 200,
-    ];
+    let rates_centirate = &rate_options.centirates;
     debug!(
         "Processing {} rates (centirate): {:?}",
         rates_centirate.len(),
@@ -41,7 +87,7 @@ pub(crate) async fn process_beatmap(
     // Calculer les scores de skillset pour tous les rates
     debug!("Calculating skillset scores with minacalc...");
     let skillset_scores = calc
-        .calculate_msd_from_string(osu_map.clone())
+        .calculate_msd_from_string(osu_map.to_string())
         .map_err(|e| BeatmapWorkerError::MinacalcError(e.to_string()))?
         .as_hashmap()
         .map_err(|e| BeatmapWorkerError::MinacalcError(e.to_string()))?;
@@ -50,9 +96,12 @@ pub(crate) async fn process_beatmap(
         skillset_scores.len()
     );
 
-    beatmap_row.main_pattern = determine_main_pattern(&skillset_scores["1.0"], &parsed_beatmap);
+    let mut rating = BeatmapRating {
+        main_pattern: determine_main_pattern(&skillset_scores["1.0"], &parsed_beatmap),
+        rates: Vec::new(),
+    };
     // Boucle simple: calculer et stocker le résultat (apply rate déporté dans RatesMaker)
-    for centirate in rates_centirate {
+    for &centirate in rates_centirate {
         let rate = centirate as f64 / 100.0;
         let rate_string = format!("{:.1}", rate);
 
@@ -62,33 +111,43 @@ pub(crate) async fn process_beatmap(
                 centirate, rate_string, scores.overall, scores.stream, scores.jumpstream
             );
 
+            let processed_rate = match storage_key {
+                Some(storage_key) => process_single_rate(
+                    centirate as i64,
+                    &parsed_beatmap,
+                    storage_key,
+                    rate_options,
+                ),
+                None => render_rate(centirate as i64, &parsed_beatmap, rate_options).processed,
+            };
+
             let mut rates_maker = RatesMaker {
                 skillset_scores: scores.clone(),
-                osu_map: osu_map.clone(),
+                osu_map: osu_map.to_string(),
                 centirate,
-                drain_time: beatmap.seconds_drain as f64,
-                total_time: beatmap.seconds_total as f64,
-                bpm: beatmap.bpm as f32,
+                drain_time: timings.drain_time,
+                total_time: timings.total_time,
+                bpm: timings.bpm,
             };
 
-            let hash = process_single_rate(centirate as i64, &parsed_beatmap, beatmap.map_id as i32);
-            
-            let rates =
-                rates_from_skillset_scores(&mut rates_maker, hash)
-                    .await
-                    .unwrap();
+            let rates = rates_from_skillset_scores(
+                &mut rates_maker,
+                processed_rate.hash,
+                &rate_options.calculators,
+            )
+            .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?;
 
-            beatmap_row.rates.push(rates);
+            rating.rates.push(RatedRates {
+                rates,
+                difficulty: RateDifficulty {
+                    od: processed_rate.od as f64,
+                    hp: processed_rate.hp as f64,
+                },
+            });
         } else {
             warn!("No skillset scores found for rate: {}", rate_string);
         }
     }
 
-    let elapsed = start_all.elapsed();
-    info!(
-        "process_beatmap done: osu_id={}, elapsed_ms={}",
-        beatmap_row.osu_id.unwrap_or_default(),
-        elapsed.as_millis()
-    );
-    Ok(())
+    Ok(rating)
 }
@@ -0,0 +1,75 @@
+use crate::core::beatmap::from::beatmap_from_beatmap_extended;
+use crate::core::beatmap::timings::BeatmapTimings;
+use crate::core::beatmapset::from::beatmapset_from_beatmapset_extended;
+use crate::core::worker::process::process_beatmap;
+use crate::core::worker::r#impl::insert::insert_full_beatmapset;
+use crate::core::worker::types::BeatmapWorker;
+use crate::errors::BeatmapWorkerError;
+use crate::store;
+use crate::store::beatmap::ProcessedBeatmap;
+
+impl BeatmapWorker {
+    /// Recalcule les rates d'une beatmap déjà traitée et remplace les anciennes en base
+    pub async fn recalc_beatmap(
+        &self,
+        beatmap_row: &ProcessedBeatmap,
+    ) -> Result<(), BeatmapWorkerError> {
+        let Some(osu_id) = beatmap_row.osu_id else {
+            return Err(BeatmapWorkerError::ProcessingFailed(format!(
+                "beatmap {} has no osu_id",
+                beatmap_row.id
+            )));
+        };
+
+        let beatmap = self
+            .osu_api_service
+            .beatmap_by_osu_id(osu_id)
+            .await
+            .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?;
+        let Some(beatmapset) = &beatmap.mapset else {
+            return Err(BeatmapWorkerError::ProcessingFailed(
+                "beatmap has no mapset".to_string(),
+            ));
+        };
+        let osu_map = self
+            .osu_api_service
+            .osu_file(beatmap.map_id)
+            .await
+            .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?;
+
+        let mut beatmapset_row = beatmapset_from_beatmapset_extended(beatmapset);
+        let mut new_beatmap_row = beatmap_from_beatmap_extended(&beatmap);
+        let timings = BeatmapTimings::from_beatmap_extended(&beatmap);
+
+        // Tout calculer avant de toucher aux anciennes rates
+        let difficulties =
+            process_beatmap(&timings, osu_map, &mut new_beatmap_row, &self.config.rate).await?;
+
+        // Remplacement en une transaction : un échec laisse les anciennes rates en place
+        let database_error = |e: sqlx::Error| BeatmapWorkerError::DatabaseError(e.to_string());
+        let mut tx = self
+            .config
+            .database
+            .get_pool()
+            .begin()
+            .await
+            .map_err(database_error)?;
+        store::rates::delete_by_beatmap_id(&mut *tx, beatmap_row.id)
+            .await
+            .map_err(database_error)?;
+        store::beatmap::update_main_pattern(
+            &mut *tx,
+            beatmap_row.id,
+            &new_beatmap_row.main_pattern,
+        )
+        .await
+        .map_err(database_error)?;
+
+        // La beatmap existe déjà : seules les rates sont réinsérées
+        beatmapset_row.beatmaps.push(new_beatmap_row);
+        insert_full_beatmapset(&mut tx, &beatmapset_row, &difficulties).await?;
+        tx.commit().await.map_err(database_error)?;
+
+        Ok(())
+    }
+}
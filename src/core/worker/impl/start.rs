@@ -1,35 +1,59 @@
-use crate::core::beatmap::from::beatmap_from_beatmap_extended;
-use crate::core::beatmapset::from::beatmapset_from_beatmapset_extended;
+use crate::api::OsuApi;
+use crate::core::beatmap::from::{beatmap_from_beatmap_extended, beatmap_from_rosu_map};
+use crate::core::beatmap::timings::BeatmapTimings;
+use crate::core::beatmapset::from::{
+    beatmapset_from_beatmapset_extended, beatmapset_from_rosu_map,
+};
+use crate::core::enqueue::enqueue_hash;
+use crate::core::rating::rated::RateDifficulties;
+use crate::core::shutdown::Shutdown;
 use crate::core::worker::process::process_beatmap;
 use crate::core::worker::r#impl::insert::insert_full_beatmapset;
+use crate::core::worker::r#impl::wakeup::PendingWakeup;
 use crate::core::worker::types::BeatmapWorker;
-use crate::errors::BeatmapWorkerError;
-use crate::utils::{build_file_path, is_allowed_beatmap};
+use crate::errors::{BeatmapWorkerError, OsuApiError};
+use crate::store;
+use crate::utils::is_allowed_beatmap;
 use anyhow::Result;
 use db::models::beatmaps::beatmap::BeatmapRow;
 use db::models::beatmaps::pending_beatmap::PendingBeatmapRow;
 use db::models::other::failed_query::FailedQueryRow;
-use minacalc_rs::Calc;
+use dto::models::beatmaps::full::types::{Beatmap, Beatmapset};
+use rosu_map::section::general::GameMode as RmGameMode;
+use rosu_map::Beatmap as RmBeatmap;
 use rosu_v2::prelude::{BeatmapExtended, BeatmapsetExtended};
+use std::str::FromStr;
+use std::time::Duration;
 
 impl BeatmapWorker {
-    pub async fn start(&self) -> Result<(), BeatmapWorkerError> {
+    /// Traite la file jusqu'à ce qu'un arrêt soit demandé
+    pub async fn start(&self, shutdown: Shutdown) -> Result<(), BeatmapWorkerError> {
         tracing::info!("Beatmap worker started");
 
         // Lancer un seul worker séquentiel
-        self.start_worker(0).await;
+        let result = self.start_worker(0, &shutdown).await;
 
-        Ok(())
+        tracing::info!("Beatmap worker stopped");
+        result
     }
 
     /// Fonction dédiée pour chaque worker individuel
-    async fn start_worker(&self, worker_id: usize) {
+    ///
+    /// Plus aucune beatmap n'est prise une fois l'arrêt demandé ; celle en cours a
+    /// `shutdown_timeout` pour finir son calcul avant d'être remise dans la file.
+    /// Une insertion commencée n'est jamais interrompue, elle laisserait des lignes partielles.
+    async fn start_worker(
+        &self,
+        worker_id: usize,
+        shutdown: &Shutdown,
+    ) -> Result<(), BeatmapWorkerError> {
         tracing::info!("Worker {} started", worker_id);
 
-        // Créer une instance locale de calculateur
-        let calc = Calc::new().unwrap();
+        let mut wakeup = PendingWakeup::connect(self.config.database.get_pool()).await;
+        // Erreurs transitoires de l'API à la suite, pour espacer les nouvelles tentatives
+        let mut transient_failures = 0;
 
-        loop {
+        while !shutdown.is_requested() {
             tracing::debug!("Worker {}: Checking for pending beatmaps...", worker_id);
             let pool = self.config.database.get_pool();
             let pending_beatmaps = PendingBeatmapRow::last_pending_beatmap(&pool).await;
@@ -38,15 +62,21 @@ impl BeatmapWorker {
                 Ok(Some(beatmap)) => beatmap,
                 Ok(None) => {
                     tracing::debug!(
-                        "Worker {}: No pending beatmaps found, sleeping for 10 seconds",
-                        worker_id
+                        "Worker {}: No pending beatmaps found, waiting up to {:?}",
+                        worker_id,
+                        self.config.worker.poll_interval
                     );
-                    tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+                    wakeup
+                        .wait(self.config.worker.poll_interval, shutdown)
+                        .await;
                     continue;
                 }
                 Err(e) => {
                     tracing::error!("Worker {}: Database error: {}", worker_id, e);
-                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                    tokio::select! {
+                        _ = tokio::time::sleep(self.config.worker.error_backoff) => {}
+                        _ = shutdown.requested() => {}
+                    }
                     continue;
                 }
             };
@@ -105,7 +135,46 @@ impl BeatmapWorker {
                 .beatmap_by_checksum(pending_beatmap.osu_hash.clone())
                 .await
             {
-                Ok(b) => b,
+                Ok(b) => {
+                    transient_failures = 0;
+                    b
+                }
+                Err(OsuApiError::NotFound(_)) => {
+                    tracing::warn!(
+                        "Worker {}: Beatmap not found on osu! API, recording failed query: {}",
+                        worker_id,
+                        pending_beatmap.osu_hash
+                    );
+                    if let Err(e) =
+                        store::failed_query::insert(pool, &pending_beatmap.osu_hash).await
+                    {
+                        tracing::error!(
+                            "Worker {}: Failed to record failed query: {}",
+                            worker_id,
+                            e
+                        );
+                    }
+                    continue;
+                }
+                Err(e) if e.is_transient() => {
+                    transient_failures += 1;
+                    let delay =
+                        transient_backoff(self.config.worker.error_backoff, transient_failures);
+                    tracing::error!(
+                        "Worker {}: Transient error fetching beatmap, re-queuing and pausing {:?}: {}",
+                        worker_id,
+                        delay,
+                        e
+                    );
+                    self.requeue_pending(&pending_beatmap.osu_hash, worker_id)
+                        .await;
+                    // Le hash remis dans la file serait sinon repris aussitôt
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = shutdown.requested() => {}
+                    }
+                    continue;
+                }
                 Err(e) => {
                     tracing::error!(
                         "Worker {}: Failed to fetch beatmap by checksum: {}",
@@ -146,10 +215,27 @@ impl BeatmapWorker {
                 beatmapset.title
             );
 
-            if let Err(e) = self
-                .process_single_beatmap(&beatmap, beatmapset, &calc)
-                .await
-            {
+            let deadline = async {
+                shutdown.requested().await;
+                tokio::time::sleep(self.config.worker.shutdown_timeout).await;
+            };
+            let rated = tokio::select! {
+                rated = self.rate_single_beatmap(&beatmap, beatmapset) => rated,
+                _ = deadline => {
+                    self.requeue_pending(&pending_beatmap.osu_hash, worker_id)
+                        .await;
+                    return Err(BeatmapWorkerError::ShutdownTimeout(format!(
+                        "beatmap {} abandoned after {:?} and re-queued",
+                        pending_beatmap.osu_hash, self.config.worker.shutdown_timeout
+                    )));
+                }
+            };
+
+            let result = match rated {
+                Ok(rated) => self.insert_rated(rated).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
                 tracing::error!("Worker {}: Failed to process beatmap: {}", worker_id, e);
                 continue;
             }
@@ -160,33 +246,154 @@ impl BeatmapWorker {
                 beatmapset.mapset_id
             );
         }
+
+        Ok(())
     }
 
-    /// Traite une seule beatmap avec son beatmapset
-    async fn process_single_beatmap(
+    /// Remet un hash dans la file d'attente après une erreur transitoire
+    async fn requeue_pending(&self, osu_hash: &str, worker_id: usize) {
+        if let Err(e) = enqueue_hash(&self.config.database, osu_hash).await {
+            tracing::error!(
+                "Worker {}: Failed to re-queue pending beatmap {}: {}",
+                worker_id,
+                osu_hash,
+                e
+            );
+        }
+    }
+
+    /// Traite une beatmap locale : métadonnées depuis l'API, contenu depuis le fichier
+    pub async fn process_local_beatmap(
+        &self,
+        osu_hash: &str,
+        osu_map: String,
+    ) -> Result<(), BeatmapWorkerError> {
+        let beatmap = self
+            .osu_api_service
+            .beatmap_by_checksum(osu_hash.to_string())
+            .await
+            .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?;
+
+        if !is_allowed_beatmap(beatmap.mode, beatmap.cs).await {
+            return Err(BeatmapWorkerError::ProcessingFailed(format!(
+                "beatmap not allowed: mode={}, cs={}",
+                beatmap.mode, beatmap.cs
+            )));
+        }
+
+        let Some(beatmapset) = &beatmap.mapset else {
+            return Err(BeatmapWorkerError::ProcessingFailed(
+                "beatmap has no mapset".to_string(),
+            ));
+        };
+
+        let rated = self
+            .rate_beatmap_content(&beatmap, beatmapset, osu_map)
+            .await?;
+        self.insert_rated(rated).await
+    }
+
+    /// Traite une beatmap uniquement à partir du `.osu`, sans appel à l'API osu!
+    pub async fn process_offline_beatmap(&self, osu_map: String) -> Result<(), BeatmapWorkerError> {
+        let parsed = RmBeatmap::from_str(&osu_map)
+            .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?;
+
+        if parsed.mode != RmGameMode::Mania || parsed.circle_size != 4.0 {
+            return Err(BeatmapWorkerError::ProcessingFailed(format!(
+                "beatmap not allowed: mode={:?}, cs={}",
+                parsed.mode, parsed.circle_size
+            )));
+        }
+
+        let beatmapset_row = beatmapset_from_rosu_map(&parsed);
+        let beatmap_row = beatmap_from_rosu_map(&parsed);
+        let timings = BeatmapTimings::from_rosu_map(&parsed);
+
+        let rated = self
+            .rate_rows(beatmapset_row, beatmap_row, &timings, osu_map)
+            .await?;
+        self.insert_rated(rated).await
+    }
+
+    /// Télécharge et calcule une beatmap de l'API avec son beatmapset, sans rien insérer
+    async fn rate_single_beatmap(
         &self,
         beatmap: &BeatmapExtended,
         beatmapset: &BeatmapsetExtended,
-        calc: &Calc,
-    ) -> Result<(), BeatmapWorkerError> {
-        let mut beatmapset_row = beatmapset_from_beatmapset_extended(beatmapset);
-        let mut beatmap_row = beatmap_from_beatmap_extended(beatmap);
-        let Some(osu_id) = beatmap_row.osu_id else {
+    ) -> Result<RatedBeatmapset, BeatmapWorkerError> {
+        let osu_map = self
+            .osu_api_service
+            .osu_file(beatmap.map_id)
+            .await
+            .map_err(|e| BeatmapWorkerError::ProcessingFailed(e.to_string()))?;
+
+        self.rate_beatmap_content(beatmap, beatmapset, osu_map)
+            .await
+    }
+
+    /// Calcule les rates d'une beatmap de l'API à partir du contenu `.osu`
+    async fn rate_beatmap_content(
+        &self,
+        beatmap: &BeatmapExtended,
+        beatmapset: &BeatmapsetExtended,
+        osu_map: String,
+    ) -> Result<RatedBeatmapset, BeatmapWorkerError> {
+        let beatmapset_row = beatmapset_from_beatmapset_extended(beatmapset);
+        let beatmap_row = beatmap_from_beatmap_extended(beatmap);
+        if beatmap_row.osu_id.is_none() {
             return Err(BeatmapWorkerError::DatabaseError(
                 "beatmap has no osu_id".to_string(),
             ));
-        };
-        let osu_path = build_file_path(osu_id as u32);
+        }
 
-        // Utiliser le calculateur local passé en paramètre
-        let result = process_beatmap(beatmap, calc, osu_path, &mut beatmap_row).await;
+        let timings = BeatmapTimings::from_beatmap_extended(beatmap);
+        self.rate_rows(beatmapset_row, beatmap_row, &timings, osu_map)
+            .await
+    }
 
-        result?;
+    async fn rate_rows(
+        &self,
+        mut beatmapset: Beatmapset,
+        mut beatmap_row: Beatmap,
+        timings: &BeatmapTimings,
+        osu_map: String,
+    ) -> Result<RatedBeatmapset, BeatmapWorkerError> {
+        let difficulties =
+            process_beatmap(timings, osu_map, &mut beatmap_row, &self.config.rate).await?;
+        beatmapset.beatmaps.push(beatmap_row);
 
-        beatmapset_row.beatmaps.push(beatmap_row);
+        Ok(RatedBeatmapset {
+            beatmapset,
+            difficulties,
+        })
+    }
 
-        insert_full_beatmapset(self, &beatmapset_row).await?;
+    /// Insère toute la hiérarchie en une transaction
+    async fn insert_rated(&self, rated: RatedBeatmapset) -> Result<(), BeatmapWorkerError> {
+        let database_error = |e: sqlx::Error| BeatmapWorkerError::DatabaseError(e.to_string());
+        let mut tx = self
+            .config
+            .database
+            .get_pool()
+            .begin()
+            .await
+            .map_err(database_error)?;
+        insert_full_beatmapset(&mut tx, &rated.beatmapset, &rated.difficulties).await?;
+        tx.commit().await.map_err(database_error)?;
+        self.notifier.beatmap_processed(&rated.beatmapset).await;
 
         Ok(())
     }
 }
+
+/// Pause après `failures` erreurs transitoires consécutives : `error_backoff` doublé à
+/// chaque nouvel échec, jusqu'à 64 fois
+fn transient_backoff(error_backoff: Duration, failures: u32) -> Duration {
+    error_backoff * 2u32.pow(failures.saturating_sub(1).min(6))
+}
+
+/// Beatmapset dont les rates sont calculées, prêt à être inséré
+struct RatedBeatmapset {
+    beatmapset: Beatmapset,
+    difficulties: RateDifficulties,
+}
@@ -0,0 +1,68 @@
+use crate::core::enqueue::{pending_wakeup, PENDING_CHANNEL};
+use crate::core::shutdown::Shutdown;
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Waits for new pending beatmaps instead of sleeping a fixed interval
+///
+/// Woken by `LISTEN pending_beatmap` and by enqueues made in this process; the poll
+/// interval stays as a fallback when notifications are lost or unavailable.
+pub struct PendingWakeup {
+    listener: Option<PgListener>,
+}
+
+impl PendingWakeup {
+    /// Subscribe to the pending channel, falling back to polling only on failure
+    pub async fn connect(pool: &PgPool) -> Self {
+        let listener = match PgListener::connect_with(pool).await {
+            Ok(mut listener) => match listener.listen(PENDING_CHANNEL).await {
+                Ok(()) => {
+                    info!("Listening on {} for new pending beatmaps", PENDING_CHANNEL);
+                    Some(listener)
+                }
+                Err(e) => {
+                    warn!("Failed to LISTEN {}, polling only: {}", PENDING_CHANNEL, e);
+                    None
+                }
+            },
+            Err(e) => {
+                warn!("Failed to open a listener connection, polling only: {}", e);
+                None
+            }
+        };
+
+        Self { listener }
+    }
+
+    /// Return on a notification, after `poll_interval`, or once shutdown is requested
+    pub async fn wait(&mut self, poll_interval: Duration, shutdown: &Shutdown) {
+        let notification = async {
+            match &mut self.listener {
+                Some(listener) => match listener.recv().await {
+                    Ok(notification) => {
+                        debug!(
+                            "Woken up by {} for {}",
+                            PENDING_CHANNEL,
+                            notification.payload()
+                        )
+                    }
+                    // The listener reconnects on the next recv, poll meanwhile
+                    Err(e) => {
+                        warn!("Listener error, polling until it reconnects: {}", e);
+                        tokio::time::sleep(poll_interval).await;
+                    }
+                },
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            _ = notification => {}
+            _ = pending_wakeup().notified() => debug!("Woken up by an in-process enqueue"),
+            _ = tokio::time::sleep(poll_interval) => {}
+            _ = shutdown.requested() => {}
+        }
+    }
+}
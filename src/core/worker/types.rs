@@ -1,7 +1,32 @@
-use crate::api::osu::OsuApiService;
+use crate::api::OsuApi;
 use crate::config::Config;
+use crate::core::notify::Notifier;
+use std::sync::Arc;
+use std::time::Duration;
 
 pub struct BeatmapWorker {
     pub config: Config,
-    pub osu_api_service: OsuApiService,
+    pub osu_api_service: Arc<dyn OsuApi>,
+    pub notifier: Arc<dyn Notifier>,
+}
+
+/// Cadence de la boucle du worker
+#[derive(Debug, Clone)]
+pub struct WorkerOptions {
+    /// Attente maximale quand la file est vide, un enqueue réveille le worker plus tôt
+    pub poll_interval: Duration,
+    /// Attente après une erreur de base de données
+    pub error_backoff: Duration,
+    /// Temps laissé à la beatmap en cours après SIGTERM/SIGINT
+    pub shutdown_timeout: Duration,
+}
+
+impl Default for WorkerOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(10),
+            error_backoff: Duration::from_secs(5),
+            shutdown_timeout: Duration::from_secs(60),
+        }
+    }
 }
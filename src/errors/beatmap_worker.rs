@@ -17,4 +17,7 @@ pub enum BeatmapWorkerError {
     #[error("Database error: {0}")]
     #[allow(dead_code)]
     DatabaseError(String),
+
+    #[error("Shutdown timed out: {0}")]
+    ShutdownTimeout(String),
 }
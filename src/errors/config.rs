@@ -1,10 +1,60 @@
+use std::fmt::Display;
+use std::path::Path;
 use thiserror::Error;
 
+/// Ordre dans lequel les valeurs sont recherchées, rappelé dans les messages d'erreur
+pub const PRECEDENCE: &str =
+    "values are read from --set, then the environment, then the config file, then defaults";
+
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("Failed to load environment variables: {0}")]
     EnvLoadError(#[from] dotenvy::Error),
 
-    #[error("Missing required environment variable: {0}")]
-    MissingVariable(String),
+    #[error("Invalid config file {path}: {reason}")]
+    File { path: String, reason: String },
+
+    #[error("Missing required setting {name} ({reason}); {}", PRECEDENCE)]
+    Missing { name: String, reason: String },
+
+    #[error("Invalid value for {name}: {reason}; {}", PRECEDENCE)]
+    InvalidValue { name: String, reason: String },
+
+    #[error("Could not parse {name}={value:?} as {expected}; {}", PRECEDENCE)]
+    ParseError {
+        name: String,
+        value: String,
+        expected: String,
+    },
+}
+
+impl ConfigError {
+    pub fn missing(name: &str, reason: &str) -> Self {
+        ConfigError::Missing {
+            name: name.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn invalid(name: &str, reason: impl Into<String>) -> Self {
+        ConfigError::InvalidValue {
+            name: name.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn parse(name: &str, value: impl Into<String>, expected: &str) -> Self {
+        ConfigError::ParseError {
+            name: name.to_string(),
+            value: value.into(),
+            expected: expected.to_string(),
+        }
+    }
+
+    pub fn file(path: &Path, reason: impl Display) -> Self {
+        ConfigError::File {
+            path: path.display().to_string(),
+            reason: reason.to_string(),
+        }
+    }
 }
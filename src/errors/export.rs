@@ -0,0 +1,20 @@
+use crate::errors::OsuApiError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Export failed: {0}")]
+    Failed(String),
+}
+
+impl From<OsuApiError> for ExportError {
+    fn from(error: OsuApiError) -> Self {
+        match error {
+            OsuApiError::NotFound(context) => ExportError::NotFound(context),
+            other => ExportError::Failed(other.to_string()),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::errors::{ExportError, OsuApiError};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::json;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum HttpError {
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Too many requests")]
+    TooManyRequests,
+
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl HttpError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            HttpError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            HttpError::NotFound(_) => StatusCode::NOT_FOUND,
+            HttpError::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            HttpError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for HttpError {
+    fn into_response(self) -> Response {
+        if let HttpError::Internal(message) = &self {
+            tracing::error!("HTTP internal error: {}", message);
+        }
+
+        (self.status(), Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+impl From<OsuApiError> for HttpError {
+    fn from(error: OsuApiError) -> Self {
+        match error {
+            OsuApiError::NotFound(context) => HttpError::NotFound(context),
+            other => HttpError::Internal(other.to_string()),
+        }
+    }
+}
+
+impl From<QueryError> for HttpError {
+    fn from(error: QueryError) -> Self {
+        match error {
+            QueryError::InvalidRequest(message) => HttpError::BadRequest(message),
+            QueryError::NotFound(context) => HttpError::NotFound(context),
+            other => HttpError::Internal(other.to_string()),
+        }
+    }
+}
+
+impl From<ExportError> for HttpError {
+    fn from(error: ExportError) -> Self {
+        match error {
+            ExportError::NotFound(context) => HttpError::NotFound(context),
+            other => HttpError::Internal(other.to_string()),
+        }
+    }
+}
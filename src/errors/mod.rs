@@ -1,6 +1,16 @@
 pub mod beatmap_worker;
 pub mod config;
+pub mod export;
+pub mod http;
+pub mod osu_api;
+pub mod query;
+pub mod startup;
 
 pub use beatmap_worker::BeatmapWorkerError;
 #[allow(unused_imports)]
 pub use config::ConfigError;
+pub use export::ExportError;
+pub use http::HttpError;
+pub use osu_api::OsuApiError;
+pub use query::QueryError;
+pub use startup::StartupError;
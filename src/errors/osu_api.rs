@@ -0,0 +1,63 @@
+use rosu_v2::error::OsuError;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum OsuApiError {
+    #[error("Not found on osu! API: {0}")]
+    NotFound(String),
+
+    #[error("Transient osu! API error: {0}")]
+    Transient(String),
+
+    /// 429, with the delay asked by the `Retry-After` header when it could be read
+    #[error("Rate limited by osu!: {context}")]
+    RateLimited {
+        context: String,
+        retry_after: Option<Duration>,
+    },
+
+    #[error("osu! API error: {0}")]
+    Other(String),
+}
+
+impl OsuApiError {
+    /// Whether the request may succeed if retried later
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            OsuApiError::Transient(_) | OsuApiError::RateLimited { .. }
+        )
+    }
+
+    /// Delay requested by osu! before the next attempt
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            OsuApiError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    pub fn from_osu_error(error: OsuError, context: &str) -> Self {
+        match &error {
+            OsuError::NotFound { .. } => OsuApiError::NotFound(context.to_string()),
+            // rosu-v2 does not expose the response headers, hence no `Retry-After` here
+            OsuError::Response { status, .. } if status.as_u16() == 429 => {
+                OsuApiError::RateLimited {
+                    context: format!("{}: {}", context, error),
+                    retry_after: None,
+                }
+            }
+            OsuError::Response { status, .. } if status.is_server_error() => {
+                OsuApiError::Transient(format!("{}: {}", context, error))
+            }
+            OsuError::ServiceUnavailable { .. }
+            | OsuError::RequestTimeout { .. }
+            | OsuError::Request { .. }
+            | OsuError::ChunkingResponse { .. } => {
+                OsuApiError::Transient(format!("{}: {}", context, error))
+            }
+            _ => OsuApiError::Other(format!("{}: {}", context, error)),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum QueryError {
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StartupError {
+    #[error("Database unreachable after {attempts} attempt(s): {reason}")]
+    DatabaseUnavailable { attempts: u32, reason: String },
+
+    #[error("Database migration failed: {0}")]
+    Migration(String),
+}
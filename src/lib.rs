@@ -6,9 +6,13 @@
 //! - Managing beatmap workers and processing pipelines
 
 pub mod api;
+pub mod bot;
+pub mod cli;
 pub mod config;
 pub mod core;
 pub mod errors;
+pub mod server;
+pub mod store;
 pub mod utils;
 
 // Re-export config
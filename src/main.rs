@@ -1,11 +1,6 @@
-mod api;
-mod config;
-mod core;
-mod errors;
-mod utils;
-
-use api::osu::OsuApiService;
-use config::Config;
+use clap::Parser;
+use pendora::cli::{self, Cli};
+use std::process::ExitCode;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_subscriber::{fmt, layer::SubscriberExt, EnvFilter, Layer};
 
@@ -44,33 +39,19 @@ fn init_logging() -> tracing_appender::non_blocking::WorkerGuard {
 }
 
 #[tokio::main]
-async fn main() {
-    let _guard = init_logging();
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let guard = init_logging();
 
-    // Load configuration
-    let config = match Config::load().await {
-        Ok(config) => config,
+    let code = match cli::run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
-            tracing::error!("Error while loading config: {}", e);
-            std::process::exit(1);
+            tracing::error!("{}", e);
+            ExitCode::FAILURE
         }
     };
 
-    let osu_api_service = OsuApiService::new(
-        config.osu_client_id.clone(),
-        config.osu_client_secret.clone(),
-    )
-    .await
-    .unwrap();
-    tracing::info!("Application started successfully");
-
-    let beatmap_worker = core::worker::BeatmapWorker {
-        config,
-        osu_api_service,
-    };
-    tokio::select! {
-        result = beatmap_worker.start() => {
-            tracing::info!("BeatmapWorker finished: {:?}", result);
-        }
-    }
+    // Vider le fichier de logs avant de quitter, `process::exit` ne le ferait pas
+    drop(guard);
+    code
 }
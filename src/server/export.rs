@@ -0,0 +1,43 @@
+use crate::core::export::osz::{export_beatmap_osz, export_beatmapset_osz};
+use crate::errors::HttpError;
+use crate::server::AppState;
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+
+/// `GET /export/beatmap/{id}` : la difficulté originale et toutes ses rates
+pub async fn export_beatmap(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Response, HttpError> {
+    let osz = export_beatmap_osz(&state.config.database, state.osu_api.as_ref(), id).await?;
+
+    Ok(osz_response(format!("{}.osz", id), osz))
+}
+
+/// `GET /export/beatmapset/{id}` : toutes les difficultés traitées du beatmapset
+pub async fn export_beatmapset(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Response, HttpError> {
+    let osz = export_beatmapset_osz(&state.config.database, state.osu_api.as_ref(), id).await?;
+
+    Ok(osz_response(format!("{}.osz", id), osz))
+}
+
+fn osz_response(file_name: String, osz: Vec<u8>) -> Response {
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                "application/x-osu-beatmap-archive".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        osz,
+    )
+        .into_response()
+}
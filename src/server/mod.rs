@@ -0,0 +1,119 @@
+mod export;
+mod rate;
+pub mod rate_limit;
+mod read;
+
+use crate::api::OsuApi;
+use crate::config::Config;
+use crate::core::shutdown::Shutdown;
+use crate::errors::HttpError;
+use anyhow::Result;
+use axum::extract::{ConnectInfo, DefaultBodyLimit, Request, State};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::Router;
+use rate_limit::IpRateLimiter;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Options du serveur HTTP
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    pub bind: SocketAddr,
+    /// Taille maximale d'un `.osu` envoyé dans le corps de la requête
+    pub max_body_bytes: usize,
+    pub requests_per_minute_per_ip: u32,
+    /// Nombre de calculs de rating exécutés en parallèle
+    pub max_concurrent_calculations: usize,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([0, 0, 0, 0], 8080)),
+            max_body_bytes: 2 * 1024 * 1024,
+            requests_per_minute_per_ip: 30,
+            max_concurrent_calculations: 2,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    pub config: Arc<Config>,
+    pub osu_api: Arc<dyn OsuApi>,
+    pub limiter: Arc<IpRateLimiter>,
+    pub calculations: Arc<Semaphore>,
+}
+
+impl AppState {
+    pub fn new(config: Config, osu_api: Arc<dyn OsuApi>) -> Self {
+        let options = &config.server;
+        Self {
+            limiter: Arc::new(IpRateLimiter::per_minute(
+                options.requests_per_minute_per_ip,
+            )),
+            calculations: calculation_slots(options),
+            config: Arc::new(config),
+            osu_api,
+        }
+    }
+}
+
+/// Places de calcul de rating, partagées par toutes les commandes qui calculent
+/// à la demande (HTTP et bot Discord)
+pub fn calculation_slots(options: &ServerOptions) -> Arc<Semaphore> {
+    Arc::new(Semaphore::new(options.max_concurrent_calculations.max(1)))
+}
+
+pub fn router(state: AppState) -> Router {
+    let max_body_bytes = state.config.server.max_body_bytes;
+
+    Router::new()
+        .route("/rate", post(rate::rate_upload))
+        .route("/rate/beatmap/{id}", get(rate::rate_beatmap_id))
+        .route("/rate/hash/{hash}", get(rate::rate_hash))
+        .route("/beatmaps/search", get(read::search))
+        .route("/beatmaps/hash/{hash}", get(read::beatmap_by_rate_hash))
+        .route("/beatmaps/{id}", get(read::beatmap_by_id))
+        .route("/recommend", post(read::recommend))
+        .route("/export/beatmap/{id}", get(export::export_beatmap))
+        .route("/export/beatmapset/{id}", get(export::export_beatmapset))
+        .layer(middleware::from_fn_with_state(state.clone(), limit_per_ip))
+        .layer(DefaultBodyLimit::max(max_body_bytes))
+        .with_state(state)
+}
+
+/// Lance le serveur HTTP jusqu'à SIGTERM/SIGINT, en laissant finir les requêtes en cours
+pub async fn serve(config: Config, osu_api: Arc<dyn OsuApi>, shutdown: Shutdown) -> Result<()> {
+    let bind = config.server.bind;
+    let app = router(AppState::new(config, osu_api));
+
+    let listener = tokio::net::TcpListener::bind(bind).await?;
+    tracing::info!("HTTP server listening on {}", bind);
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move { shutdown.requested().await })
+    .await?;
+
+    tracing::info!("HTTP server stopped");
+
+    Ok(())
+}
+
+async fn limit_per_ip(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Result<Response, HttpError> {
+    if !state.limiter.check(addr.ip()).await {
+        return Err(HttpError::TooManyRequests);
+    }
+
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,109 @@
+use crate::config::parse_centirate;
+use crate::core::rating::local::{rate_osu_file_blocking, BeatmapRating};
+use crate::errors::{BeatmapWorkerError, HttpError};
+use crate::server::AppState;
+use crate::utils::rate::options::{RateOptions, DEFAULT_CENTIRATES};
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::Deserialize;
+use tokio::sync::OwnedSemaphorePermit;
+
+#[derive(Debug, Deserialize)]
+pub struct RateQuery {
+    /// Rates à calculer séparées par des virgules, celles de la config par défaut
+    pub rates: Option<String>,
+}
+
+/// `POST /rate` avec le contenu du `.osu` comme corps
+pub async fn rate_upload(
+    State(state): State<AppState>,
+    Query(query): Query<RateQuery>,
+    body: String,
+) -> Result<Json<BeatmapRating>, HttpError> {
+    if body.trim().is_empty() {
+        return Err(HttpError::BadRequest("empty .osu body".to_string()));
+    }
+
+    let options = rate_options(&state, query.rates)?;
+    let _permit = acquire_calculation(&state).await?;
+    compute(body, options).await
+}
+
+/// `GET /rate/beatmap/{id}`
+pub async fn rate_beatmap_id(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+    Query(query): Query<RateQuery>,
+) -> Result<Json<BeatmapRating>, HttpError> {
+    let options = rate_options(&state, query.rates)?;
+    let _permit = acquire_calculation(&state).await?;
+    let osu_map = state.osu_api.osu_file(id).await?;
+    compute(osu_map, options).await
+}
+
+/// `GET /rate/hash/{hash}`
+pub async fn rate_hash(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+    Query(query): Query<RateQuery>,
+) -> Result<Json<BeatmapRating>, HttpError> {
+    let options = rate_options(&state, query.rates)?;
+    let _permit = acquire_calculation(&state).await?;
+    let beatmap = state.osu_api.beatmap_by_checksum(hash).await?;
+    let osu_map = state.osu_api.osu_file(beatmap.map_id).await?;
+    compute(osu_map, options).await
+}
+
+/// Options de la config, avec les rates demandées à la place si elles sont données
+///
+/// Chaque rate n'est calculée qu'une fois, et jamais plus que les 14 rates possibles ne
+/// sont acceptées : une requête ne peut pas multiplier les calculs.
+fn rate_options(state: &AppState, rates: Option<String>) -> Result<RateOptions, HttpError> {
+    let mut options = state.config.rate.clone();
+    if let Some(rates) = rates {
+        let rates: Vec<&str> = rates.split(',').collect();
+        if rates.len() > DEFAULT_CENTIRATES.len() {
+            return Err(HttpError::BadRequest(format!(
+                "at most {} rates can be requested",
+                DEFAULT_CENTIRATES.len()
+            )));
+        }
+
+        let mut centirates = rates
+            .into_iter()
+            .map(|rate| {
+                parse_centirate(rate.trim())
+                    .ok_or_else(|| HttpError::BadRequest(format!("invalid rate {}", rate)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        centirates.sort_unstable();
+        centirates.dedup();
+        options.centirates = centirates;
+    }
+    Ok(options)
+}
+
+/// Place parmi les calculs simultanés, prise avant tout appel à l'API osu! pour
+/// que les requêtes en attente ne consomment pas son quota
+async fn acquire_calculation(state: &AppState) -> Result<OwnedSemaphorePermit, HttpError> {
+    state
+        .calculations
+        .clone()
+        .acquire_owned()
+        .await
+        .map_err(|e| HttpError::Internal(e.to_string()))
+}
+
+/// Calcule les ratings sans rien persister, l'appelant tenant une place de calcul
+async fn compute(osu_map: String, options: RateOptions) -> Result<Json<BeatmapRating>, HttpError> {
+    let rating = rate_osu_file_blocking(osu_map, options)
+        .await
+        .map_err(|e| match e {
+            BeatmapWorkerError::ProcessingFailed(_) | BeatmapWorkerError::MinacalcError(_) => {
+                HttpError::BadRequest(e.to_string())
+            }
+            other => HttpError::Internal(other.to_string()),
+        })?;
+
+    Ok(Json(rating))
+}
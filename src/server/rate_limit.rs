@@ -0,0 +1,46 @@
+use crate::api::rate_limit::TokenBucket;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Au-delà, les buckets pleins sont oubliés pour borner la mémoire
+const MAX_TRACKED_IPS: usize = 10_000;
+
+/// Un token bucket par adresse IP
+pub struct IpRateLimiter {
+    requests_per_minute: u32,
+    buckets: Mutex<HashMap<IpAddr, Arc<TokenBucket>>>,
+}
+
+impl IpRateLimiter {
+    pub fn per_minute(requests_per_minute: u32) -> Self {
+        Self {
+            requests_per_minute,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Consomme un token pour cette IP, `false` si la limite est atteinte
+    pub async fn check(&self, ip: IpAddr) -> bool {
+        let bucket = {
+            let mut buckets = self.buckets.lock().await;
+            if buckets.len() >= MAX_TRACKED_IPS && !buckets.contains_key(&ip) {
+                // Un bucket plein se comporte comme un neuf : l'oublier ne rend aucun token,
+                // contrairement à un `clear` que n'importe qui pourrait provoquer
+                buckets.retain(|_, bucket| !bucket.is_full());
+                if buckets.len() >= MAX_TRACKED_IPS {
+                    // Toutes les IP suivies consomment encore : refuser plutôt qu'en oublier une
+                    return false;
+                }
+            }
+
+            buckets
+                .entry(ip)
+                .or_insert_with(|| Arc::new(TokenBucket::per_minute(self.requests_per_minute)))
+                .clone()
+        };
+
+        bucket.try_acquire().await
+    }
+}
@@ -0,0 +1,54 @@
+use crate::core::query;
+use crate::core::query::recommend::{RecommendRequest, Recommendations};
+use crate::core::query::search::{search_beatmaps, BeatmapSearch, SearchPage};
+use crate::core::query::{beatmap_by_hash, beatmap_by_osu_id};
+use crate::errors::HttpError;
+use crate::server::AppState;
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use dto::models::beatmaps::full::types::Beatmapset;
+
+/// `GET /beatmaps/{id}` : beatmap traitée par id osu!, avec ses rates et ratings
+pub async fn beatmap_by_id(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Beatmapset>, HttpError> {
+    beatmap_by_osu_id(&state.config.database, id)
+        .await
+        .map_err(|e| HttpError::Internal(e.to_string()))?
+        .map(Json)
+        .ok_or_else(|| HttpError::NotFound(format!("beatmap {}", id)))
+}
+
+/// `GET /beatmaps/hash/{hash}` : beatmap qui possède ce fichier de rate
+pub async fn beatmap_by_rate_hash(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+) -> Result<Json<Beatmapset>, HttpError> {
+    beatmap_by_hash(&state.config.database, &hash)
+        .await
+        .map_err(|e| HttpError::Internal(e.to_string()))?
+        .map(Json)
+        .ok_or_else(|| HttpError::NotFound(format!("hash {}", hash)))
+}
+
+/// `GET /beatmaps/search` : filtres et tri en query string
+pub async fn search(
+    State(state): State<AppState>,
+    Query(search): Query<BeatmapSearch>,
+) -> Result<Json<SearchPage>, HttpError> {
+    search.validate().map_err(HttpError::BadRequest)?;
+    let page = search_beatmaps(&state.config.database, &search)
+        .await
+        .map_err(|e| HttpError::Internal(e.to_string()))?;
+    Ok(Json(page))
+}
+
+/// `POST /recommend` : beatmap-rates les plus proches d'un vecteur de skillsets
+pub async fn recommend(
+    State(state): State<AppState>,
+    Json(request): Json<RecommendRequest>,
+) -> Result<Json<Recommendations>, HttpError> {
+    let recommendations = query::recommend::recommend(&state.config.database, &request).await?;
+    Ok(Json(recommendations))
+}
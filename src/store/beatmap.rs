@@ -0,0 +1,155 @@
+use crate::store::tables::BEATMAP;
+use db::models::beatmaps::beatmap::BeatmapRow;
+use dto::models::beatmaps::full::types::Beatmap;
+use sqlx::PgExecutor;
+
+pub async fn find_id_by_osu_id(
+    executor: impl PgExecutor<'_>,
+    osu_id: i32,
+) -> Result<Option<i32>, sqlx::Error> {
+    sqlx::query_scalar(&format!("SELECT id FROM {} WHERE osu_id = $1", BEATMAP))
+        .bind(osu_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Insert a beatmap without its rates, returns its id
+pub async fn insert(
+    executor: impl PgExecutor<'_>,
+    beatmapset_id: i32,
+    beatmap: &Beatmap,
+) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "INSERT INTO {} (osu_id, beatmapset_id, difficulty, count_circles, count_sliders, \
+         count_spinners, max_combo, main_pattern, cs, ar, od, hp, mode, status) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, \
+         $9::numeric, $10::numeric, $11::numeric, $12::numeric, $13, $14) \
+         RETURNING id",
+        BEATMAP
+    ))
+    .bind(beatmap.osu_id)
+    .bind(beatmapset_id)
+    .bind(&beatmap.difficulty)
+    .bind(beatmap.count_circles)
+    .bind(beatmap.count_sliders)
+    .bind(beatmap.count_spinners)
+    .bind(beatmap.max_combo)
+    .bind(&beatmap.main_pattern)
+    .bind(beatmap.cs)
+    .bind(beatmap.ar)
+    .bind(beatmap.od)
+    .bind(beatmap.hp)
+    .bind(beatmap.mode)
+    .bind(&beatmap.status)
+    .fetch_one(executor)
+    .await
+}
+
+/// osu! ids of the processed difficulties of a beatmapset, offline ones have none
+pub async fn osu_ids_by_beatmapset_id(
+    executor: impl PgExecutor<'_>,
+    beatmapset_id: i32,
+) -> Result<Vec<i32>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT osu_id FROM {} WHERE beatmapset_id = $1 AND osu_id IS NOT NULL ORDER BY id",
+        BEATMAP
+    ))
+    .bind(beatmapset_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Columns of a processed beatmap the status refresh and recalc need
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ProcessedBeatmap {
+    pub id: i32,
+    pub osu_id: Option<i32>,
+    pub beatmapset_id: Option<i32>,
+    pub status: String,
+}
+
+impl From<BeatmapRow> for ProcessedBeatmap {
+    fn from(row: BeatmapRow) -> Self {
+        Self {
+            id: row.id,
+            osu_id: row.osu_id,
+            beatmapset_id: row.beatmapset_id,
+            status: row.status,
+        }
+    }
+}
+
+const PROCESSED_COLUMNS: &str = "id, osu_id, beatmapset_id, status";
+
+/// Every processed beatmap, oldest first
+pub async fn find_all(executor: impl PgExecutor<'_>) -> Result<Vec<ProcessedBeatmap>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT {} FROM {} ORDER BY id",
+        PROCESSED_COLUMNS, BEATMAP
+    ))
+    .fetch_all(executor)
+    .await
+}
+
+/// Processed beatmaps whose status is one of `statuses`
+pub async fn find_by_statuses(
+    executor: impl PgExecutor<'_>,
+    statuses: &[String],
+) -> Result<Vec<ProcessedBeatmap>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT {} FROM {} WHERE status = ANY($1) ORDER BY id",
+        PROCESSED_COLUMNS, BEATMAP
+    ))
+    .bind(statuses)
+    .fetch_all(executor)
+    .await
+}
+
+pub async fn update_status(
+    executor: impl PgExecutor<'_>,
+    id: i32,
+    status: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        "UPDATE {} SET status = $2, updated_at = NOW() WHERE id = $1",
+        BEATMAP
+    ))
+    .bind(id)
+    .bind(status)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Replace the main pattern after the ratings have been recomputed
+pub async fn update_main_pattern(
+    executor: impl PgExecutor<'_>,
+    id: i32,
+    main_pattern: &serde_json::Value,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        "UPDATE {} SET main_pattern = $2, updated_at = NOW() WHERE id = $1",
+        BEATMAP
+    ))
+    .bind(id)
+    .bind(main_pattern)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+pub async fn count(executor: impl PgExecutor<'_>) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", BEATMAP))
+        .fetch_one(executor)
+        .await
+}
+
+pub async fn find_by_id(
+    executor: impl PgExecutor<'_>,
+    id: i32,
+) -> Result<Option<BeatmapRow>, sqlx::Error> {
+    sqlx::query_as(&format!("SELECT * FROM {} WHERE id = $1", BEATMAP))
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+}
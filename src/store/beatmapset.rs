@@ -0,0 +1,84 @@
+use crate::store::tables::BEATMAPSET;
+use chrono::NaiveDateTime;
+use db::models::beatmaps::beatmapset::BeatmapsetRow;
+use dto::models::beatmaps::full::types::Beatmapset;
+use sqlx::PgExecutor;
+
+pub async fn find_by_id(
+    executor: impl PgExecutor<'_>,
+    id: i32,
+) -> Result<Option<BeatmapsetRow>, sqlx::Error> {
+    sqlx::query_as(&format!("SELECT * FROM {} WHERE id = $1", BEATMAPSET))
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+}
+
+pub async fn find_id_by_osu_id(
+    executor: impl PgExecutor<'_>,
+    osu_id: i32,
+) -> Result<Option<i32>, sqlx::Error> {
+    sqlx::query_scalar(&format!("SELECT id FROM {} WHERE osu_id = $1", BEATMAPSET))
+        .bind(osu_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Insert a beatmapset without its beatmaps, returns its id
+///
+/// Tags are stored as a list, split on spaces.
+pub async fn insert(
+    executor: impl PgExecutor<'_>,
+    beatmapset: &Beatmapset,
+) -> Result<i32, sqlx::Error> {
+    let tags: Option<Vec<String>> = beatmapset.tags.as_ref().map(|tags| {
+        tags.split(' ')
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect()
+    });
+
+    sqlx::query_scalar(&format!(
+        "INSERT INTO {} (osu_id, artist, artist_unicode, title, title_unicode, creator, source, \
+         tags, has_video, has_storyboard, is_explicit, is_featured, cover_url, preview_url, \
+         osu_file_url, osu_status_changed_at) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16) \
+         RETURNING id",
+        BEATMAPSET
+    ))
+    .bind(beatmapset.osu_id)
+    .bind(&beatmapset.artist)
+    .bind(&beatmapset.artist_unicode)
+    .bind(&beatmapset.title)
+    .bind(&beatmapset.title_unicode)
+    .bind(&beatmapset.creator)
+    .bind(&beatmapset.source)
+    .bind(tags)
+    .bind(beatmapset.has_video)
+    .bind(beatmapset.has_storyboard)
+    .bind(beatmapset.is_explicit)
+    .bind(beatmapset.is_featured)
+    .bind(&beatmapset.cover_url)
+    .bind(&beatmapset.preview_url)
+    .bind(&beatmapset.osu_file_url)
+    .bind(beatmapset.osu_status_changed_at)
+    .fetch_one(executor)
+    .await
+}
+
+/// Record when osu! last changed the rank status of a beatmapset
+pub async fn update_status_changed_at(
+    executor: impl PgExecutor<'_>,
+    id: i32,
+    changed_at: NaiveDateTime,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        "UPDATE {} SET osu_status_changed_at = $2, updated_at = NOW() WHERE id = $1",
+        BEATMAPSET
+    ))
+    .bind(id)
+    .bind(changed_at)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
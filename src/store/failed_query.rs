@@ -0,0 +1,19 @@
+use crate::store::tables::FAILED_QUERY;
+use sqlx::PgExecutor;
+
+/// Remember a hash unknown to osu!, checked by `FailedQueryRow::exists_by_hash`
+pub async fn insert(executor: impl PgExecutor<'_>, osu_hash: &str) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "INSERT INTO {} (osu_hash) VALUES ($1) RETURNING id",
+        FAILED_QUERY
+    ))
+    .bind(osu_hash)
+    .fetch_one(executor)
+    .await
+}
+
+pub async fn count(executor: impl PgExecutor<'_>) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", FAILED_QUERY))
+        .fetch_one(executor)
+        .await
+}
@@ -0,0 +1,78 @@
+//! Queries the pinned `db` crate does not provide, over its pool and row types
+//!
+//! The schema changes they rely on live in `migrations/` at the root of the repository.
+//! `migrate` applies each of them once, recording it in `MIGRATIONS_TABLE`.
+
+pub mod beatmap;
+pub mod beatmapset;
+pub mod failed_query;
+pub mod pending;
+pub mod rates;
+pub mod rating;
+pub mod recommend;
+pub mod search;
+pub mod tables;
+
+use sqlx::PgPool;
+use tracing::info;
+
+/// Migrations already applied, by name
+const MIGRATIONS_TABLE: &str = "pendora_migrations";
+
+/// Arbitrary key of the advisory lock held while migrating, so that processes
+/// starting together apply each migration only once
+const MIGRATION_LOCK: i64 = 0x7065_6e64_6f72_61;
+
+/// Embedded `migrations/`, in the order they are applied
+const MIGRATIONS: &[(&str, &str)] = &[
+    (
+        "0001_rates_difficulty",
+        include_str!("../../migrations/0001_rates_difficulty.sql"),
+    ),
+    (
+        "0002_pending_notify",
+        include_str!("../../migrations/0002_pending_notify.sql"),
+    ),
+];
+
+/// Apply the migrations not applied yet, each one in its own transaction
+pub async fn migrate(pool: &PgPool) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        "CREATE TABLE IF NOT EXISTS {} \
+         (name TEXT PRIMARY KEY, applied_at TIMESTAMPTZ NOT NULL DEFAULT now())",
+        MIGRATIONS_TABLE
+    ))
+    .execute(pool)
+    .await?;
+
+    for (name, sql) in MIGRATIONS {
+        let mut tx = pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(MIGRATION_LOCK)
+            .execute(&mut *tx)
+            .await?;
+
+        let applied: bool = sqlx::query_scalar(&format!(
+            "SELECT EXISTS (SELECT 1 FROM {} WHERE name = $1)",
+            MIGRATIONS_TABLE
+        ))
+        .bind(*name)
+        .fetch_one(&mut *tx)
+        .await?;
+        if applied {
+            continue;
+        }
+
+        sqlx::raw_sql(sql).execute(&mut *tx).await?;
+        sqlx::query(&format!(
+            "INSERT INTO {} (name) VALUES ($1)",
+            MIGRATIONS_TABLE
+        ))
+        .bind(*name)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        info!("Migration {} applied", name);
+    }
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use crate::store::tables::PENDING_BEATMAP;
+use sqlx::PgExecutor;
+
+/// Add a hash to the queue read by `PendingBeatmapRow::last_pending_beatmap`
+pub async fn insert(executor: impl PgExecutor<'_>, osu_hash: &str) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "INSERT INTO {} (osu_hash) VALUES ($1) RETURNING id",
+        PENDING_BEATMAP
+    ))
+    .bind(osu_hash)
+    .fetch_one(executor)
+    .await
+}
+
+pub async fn count(executor: impl PgExecutor<'_>) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", PENDING_BEATMAP))
+        .fetch_one(executor)
+        .await
+}
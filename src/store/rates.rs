@@ -0,0 +1,93 @@
+use crate::core::rating::rated::RateDifficulty;
+use crate::store::tables::{BEATMAP_MANIA_RATING, BEATMAP_RATING, RATES};
+use db::models::beatmaps::rates::RatesRow;
+use dto::models::rate::Rates;
+use sqlx::PgExecutor;
+
+/// Insert a rate with its OD/HP in a single statement, `RatesRow` having no such columns
+///
+/// OD/HP stay NULL when `difficulty` is `None`. Returns the id of the new row.
+pub async fn insert(
+    executor: impl PgExecutor<'_>,
+    beatmap_id: i32,
+    rates: &Rates,
+    difficulty: Option<&RateDifficulty>,
+) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "INSERT INTO {} (beatmap_id, osu_hash, centirate, drain_time, total_time, bpm, od, hp) \
+         VALUES ($1, $2, $3, $4, $5, $6::numeric, $7::numeric, $8::numeric) RETURNING id",
+        RATES
+    ))
+    .bind(beatmap_id)
+    .bind(rates.osu_hash.clone().unwrap_or_default())
+    .bind(rates.centirate)
+    .bind(rates.drain_time)
+    .bind(rates.total_time)
+    .bind(rates.bpm as f64)
+    .bind(difficulty.map(|d| d.od))
+    .bind(difficulty.map(|d| d.hp))
+    .fetch_one(executor)
+    .await
+}
+
+/// Hash of every stored rate, to tell rate files still referenced from orphans
+pub async fn all_hashes(executor: impl PgExecutor<'_>) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(&format!("SELECT DISTINCT osu_hash FROM {}", RATES))
+        .fetch_all(executor)
+        .await
+}
+
+pub async fn count(executor: impl PgExecutor<'_>) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", RATES))
+        .fetch_one(executor)
+        .await
+}
+
+/// Delete the rates of a beatmap with their ratings, in one statement so that a
+/// failure leaves them all in place. Returns the number of rates deleted.
+pub async fn delete_by_beatmap_id(
+    executor: impl PgExecutor<'_>,
+    beatmap_id: i32,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(&format!(
+        "WITH old_rates AS (SELECT id FROM {rates} WHERE beatmap_id = $1), \
+         old_ratings AS (SELECT id FROM {rating} WHERE rates_id IN (SELECT id FROM old_rates)), \
+         deleted_mania AS (DELETE FROM {mania} WHERE rating_id IN (SELECT id FROM old_ratings)), \
+         deleted_ratings AS (DELETE FROM {rating} WHERE id IN (SELECT id FROM old_ratings)) \
+         DELETE FROM {rates} WHERE id IN (SELECT id FROM old_rates)",
+        rates = RATES,
+        rating = BEATMAP_RATING,
+        mania = BEATMAP_MANIA_RATING,
+    ))
+    .bind(beatmap_id)
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Rate stored under this file hash, the oldest one if several beatmaps share it
+pub async fn find_by_osu_hash(
+    executor: impl PgExecutor<'_>,
+    osu_hash: &str,
+) -> Result<Option<RatesRow>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT * FROM {} WHERE osu_hash = $1 ORDER BY id LIMIT 1",
+        RATES
+    ))
+    .bind(osu_hash)
+    .fetch_optional(executor)
+    .await
+}
+
+pub async fn find_by_beatmap_id(
+    executor: impl PgExecutor<'_>,
+    beatmap_id: i32,
+) -> Result<Vec<RatesRow>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT * FROM {} WHERE beatmap_id = $1 ORDER BY centirate, id",
+        RATES
+    ))
+    .bind(beatmap_id)
+    .fetch_all(executor)
+    .await
+}
@@ -0,0 +1,75 @@
+use crate::store::tables::{BEATMAP_MANIA_RATING, BEATMAP_RATING};
+use db::models::rating::beatmap_mania_rating::BeatmapManiaRatingRow;
+use db::models::rating::beatmap_rating::BeatmapRatingRow;
+use dto::models::rate::{ManiaRating, Rating};
+use sqlx::PgExecutor;
+
+/// Insert a rating without its mode rating, returns its id
+pub async fn insert(
+    executor: impl PgExecutor<'_>,
+    rates_id: i32,
+    rating: &Rating,
+) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "INSERT INTO {} (rates_id, rating, rating_type) VALUES ($1, $2::numeric, $3) RETURNING id",
+        BEATMAP_RATING
+    ))
+    .bind(rates_id)
+    .bind(rating.rating)
+    .bind(&rating.rating_type)
+    .fetch_one(executor)
+    .await
+}
+
+pub async fn insert_mania(
+    executor: impl PgExecutor<'_>,
+    rating_id: i32,
+    mania: &ManiaRating,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        "INSERT INTO {} (rating_id, stream, jumpstream, handstream, stamina, jackspeed, \
+         chordjack, technical) \
+         VALUES ($1, $2::numeric, $3::numeric, $4::numeric, $5::numeric, $6::numeric, \
+         $7::numeric, $8::numeric)",
+        BEATMAP_MANIA_RATING
+    ))
+    .bind(rating_id)
+    .bind(mania.stream)
+    .bind(mania.jumpstream)
+    .bind(mania.handstream)
+    .bind(mania.stamina)
+    .bind(mania.jackspeed)
+    .bind(mania.chordjack)
+    .bind(mania.technical)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Ratings of a rate, one per rating type
+pub async fn find_by_rates_id(
+    executor: impl PgExecutor<'_>,
+    rates_id: i32,
+) -> Result<Vec<BeatmapRatingRow>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT * FROM {} WHERE rates_id = $1 ORDER BY id",
+        BEATMAP_RATING
+    ))
+    .bind(rates_id)
+    .fetch_all(executor)
+    .await
+}
+
+/// Mania skillsets of a rating, absent for rating types without them
+pub async fn mania_by_rating_id(
+    executor: impl PgExecutor<'_>,
+    rating_id: i32,
+) -> Result<Option<BeatmapManiaRatingRow>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT * FROM {} WHERE rating_id = $1",
+        BEATMAP_MANIA_RATING
+    ))
+    .bind(rating_id)
+    .fetch_optional(executor)
+    .await
+}
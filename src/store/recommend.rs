@@ -0,0 +1,106 @@
+use crate::core::query::recommend::{RecommendRequest, Recommendation, SkillsetVector};
+use crate::core::rating::skillset::Skillset;
+use crate::store::search::{hit_tables, HIT_COLUMNS};
+use crate::store::tables::{BEATMAP_MANIA_RATING, BEATMAP_RATING, RATES};
+use sqlx::{PgExecutor, Postgres, QueryBuilder};
+
+/// Rates with mania skillsets closest to `target`, by weighted euclidean distance
+pub async fn nearest(
+    executor: impl PgExecutor<'_>,
+    request: &RecommendRequest,
+    target: &SkillsetVector,
+) -> Result<Vec<Recommendation>, sqlx::Error> {
+    let mut query = QueryBuilder::<Postgres>::new(format!("SELECT {}, sqrt(", HIT_COLUMNS));
+    for (i, skillset) in Skillset::ALL.into_iter().enumerate() {
+        if i > 0 {
+            query.push(" + ");
+        }
+        query
+            .push_bind(request.weights.get(skillset))
+            .push(format!(" * power(mr.{}::float8 - ", skillset.column()))
+            .push_bind(target.get(skillset))
+            .push(", 2)");
+    }
+    query.push(format!(") AS distance FROM {}", hit_tables()));
+
+    query
+        .push(" WHERE br.rating_type = ")
+        .push_bind(request.rating_type.clone());
+    query.push(" AND mr.id IS NOT NULL");
+    if let Some(keys) = request.keys {
+        query.push(" AND b.cs = ").push_bind(keys).push("::numeric");
+    }
+    if let Some(pattern) = &request.pattern {
+        query
+            .push(" AND b.main_pattern ? ")
+            .push_bind(pattern.clone());
+    }
+    if !request.recent_hashes.is_empty() {
+        // Never recommend the maps the target was built from
+        query
+            .push(" AND r.osu_hash <> ALL(")
+            .push_bind(request.recent_hashes.clone())
+            .push(")");
+    }
+
+    query
+        .push(" ORDER BY distance ASC, r.id ASC LIMIT ")
+        .push_bind(request.limit() as i64);
+
+    query
+        .build_query_as::<Recommendation>()
+        .fetch_all(executor)
+        .await
+}
+
+/// Mean skillsets of the given rates for one rating type, None if none is processed
+pub async fn average_skillsets(
+    executor: impl PgExecutor<'_>,
+    rating_type: &str,
+    hashes: &[String],
+) -> Result<Option<SkillsetVector>, sqlx::Error> {
+    let row: (
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+    ) = sqlx::query_as(&format!(
+        "SELECT avg(mr.stream)::float8, avg(mr.jumpstream)::float8, \
+         avg(mr.handstream)::float8, avg(mr.stamina)::float8, \
+         avg(mr.jackspeed)::float8, avg(mr.chordjack)::float8, \
+         avg(mr.technical)::float8 \
+         FROM {} r \
+         JOIN {} br ON br.rates_id = r.id \
+         JOIN {} mr ON mr.rating_id = br.id \
+         WHERE br.rating_type = $1 AND r.osu_hash = ANY($2)",
+        RATES, BEATMAP_RATING, BEATMAP_MANIA_RATING
+    ))
+    .bind(rating_type)
+    .bind(hashes)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(match row {
+        (
+            Some(stream),
+            Some(jumpstream),
+            Some(handstream),
+            Some(stamina),
+            Some(jackspeed),
+            Some(chordjack),
+            Some(technical),
+        ) => Some(SkillsetVector {
+            stream,
+            jumpstream,
+            handstream,
+            stamina,
+            jackspeed,
+            chordjack,
+            technical,
+        }),
+        _ => None,
+    })
+}
@@ -0,0 +1,116 @@
+use crate::core::query::search::{BeatmapSearch, SearchHit, SearchSort, SortOrder};
+use crate::store::tables::{BEATMAP, BEATMAPSET, BEATMAP_MANIA_RATING, BEATMAP_RATING, RATES};
+use sqlx::{PgExecutor, Postgres, QueryBuilder};
+
+/// Columns selected into a `SearchHit`, aliases of `hit_tables`
+pub(crate) const HIT_COLUMNS: &str = "b.osu_id AS beatmap_osu_id, s.osu_id AS beatmapset_osu_id, \
+     s.artist, s.title, s.creator, b.difficulty, b.status, b.main_pattern, \
+     r.osu_hash, r.centirate, r.bpm::float8 AS bpm, r.drain_time, \
+     br.rating_type, br.rating::float8 AS rating, \
+     mr.stream::float8 AS stream, mr.jumpstream::float8 AS jumpstream, \
+     mr.handstream::float8 AS handstream, mr.stamina::float8 AS stamina, \
+     mr.jackspeed::float8 AS jackspeed, mr.chordjack::float8 AS chordjack, \
+     mr.technical::float8 AS technical";
+
+/// One row per rate and rating type
+pub(crate) fn hit_tables() -> String {
+    format!(
+        "{} r \
+         JOIN {} b ON b.id = r.beatmap_id \
+         JOIN {} s ON s.id = b.beatmapset_id \
+         JOIN {} br ON br.rates_id = r.id \
+         LEFT JOIN {} mr ON mr.rating_id = br.id",
+        RATES, BEATMAP, BEATMAPSET, BEATMAP_RATING, BEATMAP_MANIA_RATING
+    )
+}
+
+/// One page of the rates matching `search`, as given by its `page` and `per_page`
+pub async fn search(
+    executor: impl PgExecutor<'_>,
+    search: &BeatmapSearch,
+) -> Result<Vec<SearchHit>, sqlx::Error> {
+    let mut query = QueryBuilder::<Postgres>::new(format!(
+        "SELECT {} FROM {} WHERE TRUE",
+        HIT_COLUMNS,
+        hit_tables()
+    ));
+
+    if let Some(rating_type) = &search.rating_type {
+        query
+            .push(" AND br.rating_type = ")
+            .push_bind(rating_type.clone());
+    }
+    if let Some(min) = search.min_rating {
+        query
+            .push(" AND br.rating >= ")
+            .push_bind(min)
+            .push("::numeric");
+    }
+    if let Some(max) = search.max_rating {
+        query
+            .push(" AND br.rating <= ")
+            .push_bind(max)
+            .push("::numeric");
+    }
+    if let Some(skillset) = search.skillset {
+        // Column names come from the enum, never from user input
+        let column = format!("mr.{}", skillset.column());
+        if let Some(min) = search.min_skillset {
+            query
+                .push(format!(" AND {} >= ", column))
+                .push_bind(min)
+                .push("::numeric");
+        }
+        if let Some(max) = search.max_skillset {
+            query
+                .push(format!(" AND {} <= ", column))
+                .push_bind(max)
+                .push("::numeric");
+        }
+    }
+    if let Some(pattern) = &search.pattern {
+        query
+            .push(" AND b.main_pattern ? ")
+            .push_bind(pattern.clone());
+    }
+    if let Some(keys) = search.keys {
+        query.push(" AND b.cs = ").push_bind(keys).push("::numeric");
+    }
+    if let Some(status) = &search.status {
+        query.push(" AND b.status = ").push_bind(status.clone());
+    }
+    if let Some(rate) = search.rate {
+        query
+            .push(" AND r.centirate = ")
+            .push_bind((rate * 100.0).round() as i32);
+    }
+
+    let sort_column = match (search.sort, search.skillset) {
+        (SearchSort::Rating, _) | (SearchSort::Skillset, None) => "br.rating".to_string(),
+        (SearchSort::Skillset, Some(skillset)) => format!("mr.{}", skillset.column()),
+        (SearchSort::Bpm, _) => "r.bpm".to_string(),
+        (SearchSort::DrainTime, _) => "r.drain_time".to_string(),
+        (SearchSort::Centirate, _) => "r.centirate".to_string(),
+        (SearchSort::Id, _) => "r.id".to_string(),
+    };
+    let order = match search.order {
+        SortOrder::Asc => "ASC NULLS LAST",
+        SortOrder::Desc => "DESC NULLS LAST",
+    };
+    query.push(format!(
+        " ORDER BY {} {}, r.id ASC, br.id ASC",
+        sort_column, order
+    ));
+
+    let per_page = search.per_page() as i64;
+    query
+        .push(" LIMIT ")
+        .push_bind(per_page)
+        .push(" OFFSET ")
+        .push_bind((search.page() - 1) as i64 * per_page);
+
+    query
+        .build_query_as::<SearchHit>()
+        .fetch_all(executor)
+        .await
+}
@@ -0,0 +1,9 @@
+//! Tables of the `db` crate schema used by the queries of this module
+
+pub const BEATMAP: &str = "beatmap";
+pub const BEATMAPSET: &str = "beatmapset";
+pub const RATES: &str = "rates";
+pub const BEATMAP_RATING: &str = "beatmap_rating";
+pub const BEATMAP_MANIA_RATING: &str = "beatmap_mania_rating";
+pub const PENDING_BEATMAP: &str = "pending_beatmap";
+pub const FAILED_QUERY: &str = "failed_query";
@@ -4,6 +4,24 @@ use std::str::FromStr;
 
 // removed unused get_quaver_rating
 
+/// Calculateurs lancés pour chaque rate, en plus de minacalc (toujours calculé)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalculatorOptions {
+    /// Star rating osu! (rosu-pp)
+    pub osu: bool,
+    /// Rating sunnyxxy (ssrrr)
+    pub sunnyxxy: bool,
+}
+
+impl Default for CalculatorOptions {
+    fn default() -> Self {
+        Self {
+            osu: true,
+            sunnyxxy: true,
+        }
+    }
+}
+
 pub fn get_sunnyxxy_rating(osu_map: &str, centirate: i64) -> f64 {
     let preprocess = preprocess(osu_map, "None", centirate).unwrap();
     let b = calculate(&preprocess).unwrap();
@@ -1,8 +1,6 @@
 pub mod calculator;
 pub mod rate;
-use anyhow::Result;
 use minacalc_rs::Ssr;
-use reqwest;
 use rosu_map::Beatmap;
 use rosu_v2::prelude::GameMode;
 use rosu_v2::prelude::RankStatus;
@@ -20,17 +18,24 @@ pub fn rank_status_to_string(status: &RankStatus) -> String {
     }
 }
 
+pub fn rank_status_from_string(status: &str) -> Option<RankStatus> {
+    match status {
+        "pending" => Some(RankStatus::Pending),
+        "ranked" => Some(RankStatus::Ranked),
+        "approved" => Some(RankStatus::Approved),
+        "qualified" => Some(RankStatus::Qualified),
+        "loved" => Some(RankStatus::Loved),
+        "graveyard" => Some(RankStatus::Graveyard),
+        "wip" => Some(RankStatus::WIP),
+        _ => None,
+    }
+}
+
 pub fn build_file_path(beatmap_id: u32) -> String {
     let b = format!("https://osu.ppy.sh/osu/{}", beatmap_id);
     return b;
 }
 
-pub async fn osu_file_from_url(path_url: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let response = reqwest::get(path_url).await?;
-    let body = response.text().await?;
-    Ok(body)
-}
-
 pub async fn is_allowed_beatmap(mode: GameMode, cs: f32) -> bool {
     // TODO: delete those and accept STD and 7K
     if mode != GameMode::Mania {
@@ -1,3 +1,4 @@
+use super::difficulty::DifficultyOptions;
 use rosu_map::section::hit_objects::{HitObject, HitObjectKind};
 use rosu_map::Beatmap;
 
@@ -6,12 +7,12 @@ pub struct BeatmapProcessor;
 
 impl BeatmapProcessor {
     /// Applique un centirate sur un beatmap (100 == 1.0x)
-    pub fn apply_rate(centirate: i64, map: &Beatmap) -> Beatmap {
+    pub fn apply_rate(centirate: i64, map: &Beatmap, difficulty: &DifficultyOptions) -> Beatmap {
         // Cloner pour travailler sur une copie
         let mut map = map.clone();
 
         let audio = map.audio_file.clone();
-        let formatted_rate = format!("{:.1}", centirate as f64 / 100.0);
+        let formatted_rate = Self::format_rate(centirate);
         let new_audio = match audio.rfind('.') {
             Some(dot_idx) => {
                 let (base, ext) = audio.split_at(dot_idx);
@@ -51,12 +52,29 @@ impl BeatmapProcessor {
             point.time *= time_multiplier;
         }
 
+        // Applique les ajustements d'OD/HP
+        map.overall_difficulty = difficulty.rated_od(centirate, map.overall_difficulty);
+        map.hp_drain_rate = difficulty.rated_hp(map.hp_drain_rate);
+
         // Ajoute le rate à la version sous forme normalisée (ex: " 1.2x")
         map.version.push_str(&format!(" {}x", formatted_rate));
 
+        // Ajoute l'OD/HP ajustés à la version (ex: " 1.2x (OD5.3 HP7.0)")
+        if difficulty.is_adjusted() {
+            map.version.push_str(&format!(
+                " (OD{:.1} HP{:.1})",
+                map.overall_difficulty, map.hp_drain_rate
+            ));
+        }
+
         return map;
     }
 
+    /// Formate un centirate sous forme normalisée (ex: 120 -> "1.2")
+    pub fn format_rate(centirate: i64) -> String {
+        format!("{:.1}", centirate as f64 / 100.0)
+    }
+
     /// Ajuste le timing d'un hit object selon le multiplicateur
     fn adjust_hit_object_timing(hit_object: &mut HitObject, time_multiplier: f64) {
         hit_object.start_time *= time_multiplier;
@@ -67,8 +85,12 @@ impl BeatmapProcessor {
     }
 
     /// Applique un centirate directement sur un beatmap (modifie le beatmap en place)
-    pub fn apply_rate_to_beatmap(centirate: i64, map: &mut Beatmap) {
-        let new_map = Self::apply_rate(centirate, map);
+    pub fn apply_rate_to_beatmap(
+        centirate: i64,
+        map: &mut Beatmap,
+        difficulty: &DifficultyOptions,
+    ) {
+        let new_map = Self::apply_rate(centirate, map, difficulty);
         *map = new_map;
     }
 }
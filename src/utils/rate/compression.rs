@@ -1,9 +1,101 @@
 use brotli::enc::BrotliEncoderParams;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+use std::str::FromStr;
 use tracing::debug;
 
+/// Codec utilisé pour les fichiers de rate, reflété dans l'extension du fichier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompressionCodec {
+    #[default]
+    Brotli,
+    Zstd,
+    Gzip,
+    None,
+}
+
+impl CompressionCodec {
+    pub const ALL: [CompressionCodec; 4] = [
+        CompressionCodec::Brotli,
+        CompressionCodec::Zstd,
+        CompressionCodec::Gzip,
+        CompressionCodec::None,
+    ];
+
+    /// Extension des fichiers produits avec ce codec
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CompressionCodec::Brotli => "br",
+            CompressionCodec::Zstd => "zst",
+            CompressionCodec::Gzip => "gz",
+            CompressionCodec::None => "osu",
+        }
+    }
+
+    /// Retrouve le codec à partir de l'extension d'un fichier
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|codec| codec.extension() == extension)
+    }
+
+    /// Niveau utilisé quand aucun n'est configuré
+    pub fn default_level(&self) -> i32 {
+        match self {
+            CompressionCodec::Brotli => 11,
+            CompressionCodec::Zstd => 3,
+            CompressionCodec::Gzip => 6,
+            CompressionCodec::None => 0,
+        }
+    }
+}
+
+impl FromStr for CompressionCodec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "brotli" | "br" => Ok(CompressionCodec::Brotli),
+            "zstd" | "zst" => Ok(CompressionCodec::Zstd),
+            "gzip" | "gz" => Ok(CompressionCodec::Gzip),
+            "none" => Ok(CompressionCodec::None),
+            other => Err(format!("Unknown compression codec: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for CompressionCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CompressionCodec::Brotli => "brotli",
+            CompressionCodec::Zstd => "zstd",
+            CompressionCodec::Gzip => "gzip",
+            CompressionCodec::None => "none",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Codec et niveau de compression des fichiers de rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CompressionOptions {
+    pub codec: CompressionCodec,
+    /// Niveau de compression (None utilise le niveau par défaut du codec)
+    pub level: Option<i32>,
+}
+
+impl CompressionOptions {
+    pub fn level(&self) -> i32 {
+        self.level.unwrap_or_else(|| self.codec.default_level())
+    }
+}
+
 /// Résultat d'une compression
 #[derive(Debug, Clone)]
 pub struct CompressionResult {
+    pub codec: CompressionCodec,
     pub compressed_data: Vec<u8>,
     pub original_size: usize,
     pub compressed_size: usize,
@@ -17,14 +109,15 @@ impl CompressionResult {
 
     /// Calcule le nombre d'octets économisés
     pub fn saved_bytes(&self) -> usize {
-        self.original_size - self.compressed_size
+        self.original_size.saturating_sub(self.compressed_size)
     }
 
     /// Log les détails de la compression
     pub fn log_compression_details(&self, rate: f64) {
         debug!(
-            "Rate {}: {} bytes -> {} bytes ({}% compression, {} bytes saved)",
+            "Rate {} ({}): {} bytes -> {} bytes ({}% compression, {} bytes saved)",
             rate,
+            self.codec,
             self.original_size,
             self.compressed_size,
             self.compression_ratio().round(),
@@ -33,23 +126,37 @@ impl CompressionResult {
     }
 }
 
-/// Gestionnaire de compression Brotli
+/// Gestionnaire de compression des fichiers de rate
 pub struct CompressionManager;
 
 impl CompressionManager {
-    /// Compresse des données en utilisant Brotli
-    pub fn compress_brotli(data: &[u8]) -> Result<CompressionResult, Box<dyn std::error::Error>> {
+    /// Compresse des données avec le codec et le niveau configurés
+    pub fn compress(
+        data: &[u8],
+        options: &CompressionOptions,
+    ) -> Result<CompressionResult, Box<dyn std::error::Error>> {
         let original_size = data.len();
-        let mut compressed_data = Vec::new();
-        let params = BrotliEncoderParams::default();
-        let mut input = data;
+        let level = options.level();
 
-        brotli::enc::BrotliCompress(&mut input, &mut compressed_data, &params)
-            .map_err(|e| format!("Brotli compression failed: {}", e))?;
+        let compressed_data = match options.codec {
+            CompressionCodec::Brotli => Self::compress_brotli(data, level)?,
+            CompressionCodec::Zstd => zstd::stream::encode_all(data, level)
+                .map_err(|e| format!("Zstd compression failed: {}", e))?,
+            CompressionCodec::Gzip => {
+                let mut encoder =
+                    GzEncoder::new(Vec::new(), Compression::new(level.clamp(0, 9) as u32));
+                encoder
+                    .write_all(data)
+                    .and_then(|_| encoder.finish())
+                    .map_err(|e| format!("Gzip compression failed: {}", e))?
+            }
+            CompressionCodec::None => data.to_vec(),
+        };
 
         let compressed_size = compressed_data.len();
 
         Ok(CompressionResult {
+            codec: options.codec,
             compressed_data,
             original_size,
             compressed_size,
@@ -57,9 +164,144 @@ impl CompressionManager {
     }
 
     /// Compresse une chaîne de caractères
-    pub fn compress_string(data: &str) -> Result<CompressionResult, Box<dyn std::error::Error>> {
-        Self::compress_brotli(data.as_bytes())
+    pub fn compress_string(
+        data: &str,
+        options: &CompressionOptions,
+    ) -> Result<CompressionResult, Box<dyn std::error::Error>> {
+        Self::compress(data.as_bytes(), options)
+    }
+
+    /// Décompresse des données produites avec le codec donné
+    pub fn decompress(
+        data: &[u8],
+        codec: CompressionCodec,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut decompressed_data = Vec::new();
+
+        match codec {
+            CompressionCodec::Brotli => {
+                let mut input = data;
+                brotli::BrotliDecompress(&mut input, &mut decompressed_data)
+                    .map_err(|e| format!("Brotli decompression failed: {}", e))?;
+            }
+            CompressionCodec::Zstd => {
+                decompressed_data = zstd::stream::decode_all(data)
+                    .map_err(|e| format!("Zstd decompression failed: {}", e))?;
+            }
+            CompressionCodec::Gzip => {
+                GzDecoder::new(data)
+                    .read_to_end(&mut decompressed_data)
+                    .map_err(|e| format!("Gzip decompression failed: {}", e))?;
+            }
+            CompressionCodec::None => decompressed_data.extend_from_slice(data),
+        }
+
+        Ok(decompressed_data)
+    }
+
+    /// Compresse des données en utilisant Brotli (qualité 0 à 11)
+    fn compress_brotli(data: &[u8], quality: i32) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut compressed_data = Vec::new();
+        let params = BrotliEncoderParams {
+            quality: quality.clamp(0, 11),
+            ..BrotliEncoderParams::default()
+        };
+        let mut input = data;
+
+        brotli::enc::BrotliCompress(&mut input, &mut compressed_data, &params)
+            .map_err(|e| format!("Brotli compression failed: {}", e))?;
+
+        Ok(compressed_data)
     }
 }
 
 // removed unused CompressionStats
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> String {
+        let mut osu = String::from("osu file format v14\r\n\r\n[HitObjects]\r\n");
+        for i in 0..500 {
+            osu.push_str(&format!(
+                "{},192,{},1,0,0:0:0:0:\r\n",
+                64 + (i % 4) * 128,
+                i * 125
+            ));
+        }
+        osu
+    }
+
+    fn round_trip(options: CompressionOptions) {
+        let data = sample();
+        let result = CompressionManager::compress_string(&data, &options).unwrap();
+        assert_eq!(result.codec, options.codec);
+        assert_eq!(result.original_size, data.len());
+        assert_eq!(result.compressed_size, result.compressed_data.len());
+
+        let decompressed =
+            CompressionManager::decompress(&result.compressed_data, options.codec).unwrap();
+        assert_eq!(decompressed, data.as_bytes(), "{:?}", options);
+    }
+
+    #[test]
+    fn every_codec_round_trips_at_its_default_level() {
+        for codec in CompressionCodec::ALL {
+            round_trip(CompressionOptions { codec, level: None });
+        }
+    }
+
+    #[test]
+    fn out_of_range_levels_are_clamped() {
+        for codec in [CompressionCodec::Brotli, CompressionCodec::Gzip] {
+            for level in [-5, 0, 100] {
+                round_trip(CompressionOptions {
+                    codec,
+                    level: Some(level),
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn compressing_codecs_shrink_repetitive_data() {
+        for codec in CompressionCodec::ALL {
+            let result = CompressionManager::compress_string(
+                &sample(),
+                &CompressionOptions { codec, level: None },
+            )
+            .unwrap();
+            if codec == CompressionCodec::None {
+                assert_eq!(result.saved_bytes(), 0);
+            } else {
+                assert!(result.saved_bytes() > 0, "{}", codec);
+            }
+        }
+    }
+
+    #[test]
+    fn corrupted_data_is_an_error() {
+        // Brotli has no magic number, only the codecs with a header are checked
+        let garbage = b"not compressed at all";
+        for codec in [CompressionCodec::Zstd, CompressionCodec::Gzip] {
+            assert!(
+                CompressionManager::decompress(garbage, codec).is_err(),
+                "{}",
+                codec
+            );
+        }
+    }
+
+    #[test]
+    fn codecs_are_found_by_extension_and_name() {
+        for codec in CompressionCodec::ALL {
+            assert_eq!(
+                CompressionCodec::from_extension(codec.extension()),
+                Some(codec)
+            );
+            assert_eq!(codec.to_string().parse::<CompressionCodec>(), Ok(codec));
+        }
+        assert!("lzma".parse::<CompressionCodec>().is_err());
+    }
+}
@@ -0,0 +1,48 @@
+/// Ajustement de l'OD appliqué aux rates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OdAdjustment {
+    /// Conserve l'OD original
+    #[default]
+    Keep,
+    /// Force une valeur absolue
+    Absolute(f32),
+    /// Ajuste l'OD pour que les fenêtres de jugement restent constantes en temps réel
+    ConstantWindow,
+}
+
+/// Ajustements de difficulté (OD/HP) appliqués aux rates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DifficultyOptions {
+    pub od: OdAdjustment,
+    /// HP forcé (None conserve l'HP original)
+    pub hp: Option<f32>,
+}
+
+impl DifficultyOptions {
+    /// Calcule l'OD d'une rate à partir de l'OD original
+    pub fn rated_od(&self, centirate: i64, od: f32) -> f32 {
+        match self.od {
+            OdAdjustment::Keep => od,
+            OdAdjustment::Absolute(value) => value.clamp(0.0, 10.0),
+            OdAdjustment::ConstantWindow => {
+                // Fenêtre 300 en mania : 64 - 3 * OD (ms). À un rate r, la fenêtre réelle
+                // est divisée par r, on cherche donc OD' tel que 64 - 3 * OD' = r * (64 - 3 * OD)
+                let rate = centirate as f32 / 100.0;
+                let window = 64.0 - 3.0 * od;
+                let rated = (64.0 - rate * window) / 3.0;
+                // Arrondi au dixième pour garder un fichier (et donc un hash) stable
+                ((rated * 10.0).round() / 10.0).clamp(0.0, 10.0)
+            }
+        }
+    }
+
+    /// Calcule l'HP d'une rate à partir de l'HP original
+    pub fn rated_hp(&self, hp: f32) -> f32 {
+        self.hp.map(|value| value.clamp(0.0, 10.0)).unwrap_or(hp)
+    }
+
+    /// Indique si des ajustements modifient la difficulté
+    pub fn is_adjusted(&self) -> bool {
+        self.od != OdAdjustment::Keep || self.hp.is_some()
+    }
+}
@@ -1,16 +1,244 @@
+use super::compression::{CompressionCodec, CompressionManager, CompressionOptions};
+use super::hash::hash_md5;
+use rosu_map::Beatmap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Dossier racine des fichiers de rate par défaut
+pub const DEFAULT_BEATMAP_ROOT: &str = "public/beatmap";
+
+/// Sous-dossier d'une beatmap contenant son `.osu` original
+const ORIGINAL_DIR: &str = "original";
+
+/// Préfixe des dossiers de beatmaps sans id osu!, suivi du hash de leur `.osu`
+const OFFLINE_PREFIX: &str = "offline-";
+
+static BEATMAP_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Emplacement des fichiers générés
+#[derive(Debug, Clone)]
+pub struct StorageOptions {
+    /// Dossier racine des fichiers de rate, un sous-dossier par beatmap
+    pub beatmap_root: PathBuf,
+}
+
+impl Default for StorageOptions {
+    fn default() -> Self {
+        Self {
+            beatmap_root: PathBuf::from(DEFAULT_BEATMAP_ROOT),
+        }
+    }
+}
 
 /// Gestionnaire de fichiers et dossiers pour les beatmaps
 pub struct FileManager;
 
 impl FileManager {
+    /// Fixe la racine des fichiers de rate, une seule fois au démarrage
+    pub fn init(options: &StorageOptions) {
+        if BEATMAP_ROOT.set(options.beatmap_root.clone()).is_err() {
+            tracing::warn!("Beatmap storage root already initialised, ignoring new value");
+        }
+    }
+
+    /// Racine des fichiers de rate, `public/beatmap` si `init` n'a pas été appelé
+    pub fn root() -> &'static Path {
+        BEATMAP_ROOT.get_or_init(|| PathBuf::from(DEFAULT_BEATMAP_ROOT))
+    }
+
+    /// Nom du dossier d'une beatmap : son id osu!, ou le hash de son `.osu` original
+    /// pour une map hors ligne, deux maps sans id ne partageant jamais le même dossier
+    pub fn storage_key(osu_id: Option<i32>, osu_map: &str) -> Result<String, String> {
+        match osu_id {
+            Some(osu_id) => Ok(osu_id.to_string()),
+            None => Ok(format!("{}{}", OFFLINE_PREFIX, hash_md5(osu_map)?)),
+        }
+    }
+
+    /// Dossier contenant les fichiers de rate d'une beatmap, `key` venant de `storage_key`
+    pub fn beatmap_dir(key: &str) -> PathBuf {
+        Self::root().join(key)
+    }
+
     pub fn save_compressed_file(
-        beatmap_id: i32,
+        key: &str,
         hash: &str,
+        codec: CompressionCodec,
         compressed_data: &[u8],
     ) -> Result<String, Box<dyn std::error::Error>> {
-        let file_path = format!("public/beatmap/{}/{}.br", beatmap_id, hash);
-        fs::write(&file_path, compressed_data)?;
+        let dir = Self::beatmap_dir(key);
+        let file_path = dir.join(format!("{}.{}", hash, codec.extension()));
+        // Le nom est le hash du contenu : un fichier existant est forcément identique
+        if !file_path.exists() {
+            fs::create_dir_all(&dir)?;
+            fs::write(&file_path, compressed_data)?;
+        }
+        Ok(file_path.to_string_lossy().to_string())
+    }
+
+    /// Dossier contenant le `.osu` original d'une beatmap, hors des fichiers de rate
+    pub fn original_dir(key: &str) -> PathBuf {
+        Self::beatmap_dir(key).join(ORIGINAL_DIR)
+    }
+
+    /// Sauvegarde le `.osu` original compressé, nommé par son hash comme les rates
+    pub fn save_original(
+        key: &str,
+        content: &str,
+        options: &CompressionOptions,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let dir = Self::original_dir(key);
+        let compressed = CompressionManager::compress_string(content, options)?;
+        let file_path = dir.join(format!(
+            "{}.{}",
+            hash_md5(content)?,
+            compressed.codec.extension()
+        ));
+        if !file_path.exists() {
+            fs::create_dir_all(&dir)?;
+            fs::write(&file_path, &compressed.compressed_data)?;
+        }
         Ok(file_path)
     }
+
+    /// Charge le `.osu` original d'une beatmap, None s'il n'a pas été sauvegardé
+    pub fn load_original(key: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let dir = Self::original_dir(key);
+        if !dir.is_dir() {
+            return Ok(None);
+        }
+
+        let mut files: Vec<PathBuf> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| Self::codec_from_path(path).is_some())
+            .collect();
+        // Le plus récent en premier si la beatmap a été mise à jour sur osu!
+        files.sort_by_key(|path| {
+            std::cmp::Reverse(fs::metadata(path).and_then(|m| m.modified()).ok())
+        });
+
+        match files.first() {
+            Some(path) => Ok(Some(Self::read_rate_file(path)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Charge un fichier de rate, vérifie son hash et le parse en beatmap
+    pub fn load(key: &str, hash: &str) -> Result<Beatmap, Box<dyn std::error::Error>> {
+        let content = Self::load_content(key, hash)?;
+        let map = Beatmap::from_str(&content)?;
+        Ok(map)
+    }
+
+    /// Charge le contenu `.osu` décompressé d'un fichier de rate, en vérifiant son hash
+    pub fn load_content(key: &str, hash: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let path = Self::find_rate_file(key, hash)
+            .ok_or_else(|| format!("Rate file not found for beatmap {}: {}", key, hash))?;
+        Self::read_rate_file(&path)
+    }
+
+    /// Retrouve le fichier de rate d'un hash, quel que soit son codec
+    pub fn find_rate_file(key: &str, hash: &str) -> Option<PathBuf> {
+        let dir = Self::beatmap_dir(key);
+        CompressionCodec::ALL
+            .into_iter()
+            .map(|codec| dir.join(format!("{}.{}", hash, codec.extension())))
+            .find(|path| path.is_file())
+    }
+
+    /// Lit et décompresse un fichier de rate, le hash du contenu doit correspondre au nom
+    pub fn read_rate_file(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+        let codec = Self::codec_from_path(path)
+            .ok_or_else(|| format!("Unknown rate file codec: {}", path.display()))?;
+        let expected_hash = Self::hash_from_path(path)
+            .ok_or_else(|| format!("Invalid rate file name: {}", path.display()))?;
+
+        let compressed_data = fs::read(path)?;
+        let content = String::from_utf8(CompressionManager::decompress(&compressed_data, codec)?)?;
+
+        let hash = hash_md5(&content)?;
+        if hash != expected_hash {
+            return Err(format!(
+                "Hash mismatch for {}: expected {}, got {}",
+                path.display(),
+                expected_hash,
+                hash
+            )
+            .into());
+        }
+
+        Ok(content)
+    }
+
+    /// Liste les fichiers de rate compressés d'une beatmap (triés par nom)
+    pub fn list_rate_files(key: &str) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let dir = Self::beatmap_dir(key);
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut files: Vec<PathBuf> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| Self::codec_from_path(path).is_some())
+            .collect();
+        files.sort();
+
+        Ok(files)
+    }
+
+    /// Liste tous les fichiers de rate présents sur le disque avec la clé de leur beatmap
+    pub fn list_all_rate_files() -> Result<Vec<(String, PathBuf)>, Box<dyn std::error::Error>> {
+        let root = Self::root();
+        if !root.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(root)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let Some(key) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+
+            for path in Self::list_rate_files(&key)? {
+                files.push((key.clone(), path));
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Hash d'un fichier de rate, déduit de son nom (`{hash}.{extension}`)
+    pub fn hash_from_path(path: &Path) -> Option<&str> {
+        path.file_stem().and_then(|stem| stem.to_str())
+    }
+
+    /// Codec d'un fichier de rate, déduit de son extension
+    pub fn codec_from_path(path: &Path) -> Option<CompressionCodec> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(CompressionCodec::from_extension)
+    }
+
+    /// Indique si deux chemins pointent vers le même fichier physique
+    pub fn is_same_file(a: &Path, b: &Path) -> bool {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            match (fs::metadata(a), fs::metadata(b)) {
+                (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+                _ => false,
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (a, b);
+            false
+        }
+    }
 }
@@ -0,0 +1,59 @@
+use rosu_map::Beatmap;
+
+/// Réécriture des métadonnées appliquée aux beatmaps ratées
+///
+/// Permet aux clients osu! de traiter chaque rate comme une difficulté distincte
+/// de l'originale, tout en gardant un hash déterministe pour un même rate.
+#[derive(Debug, Clone)]
+pub struct RateMetadata {
+    /// Valeur forcée pour `BeatmapID` (None conserve la valeur originale)
+    pub beatmap_id: Option<i32>,
+    /// Valeur forcée pour `BeatmapSetID` (None conserve la valeur originale)
+    pub beatmap_set_id: Option<i32>,
+    /// Tag ajouté aux tags existants, `{rate}` est remplacé par le rate (ex: "1.2")
+    pub tag: Option<String>,
+    /// Suffixe ajouté au créateur (ex: " (pendora)")
+    pub creator_suffix: Option<String>,
+}
+
+impl Default for RateMetadata {
+    fn default() -> Self {
+        Self {
+            beatmap_id: Some(-1),
+            beatmap_set_id: Some(-1),
+            tag: Some("pendora rate {rate}x".to_string()),
+            creator_suffix: None,
+        }
+    }
+}
+
+impl RateMetadata {
+    /// Applique la réécriture sur un beatmap déjà raté
+    pub fn apply(&self, formatted_rate: &str, map: &mut Beatmap) {
+        if let Some(beatmap_id) = self.beatmap_id {
+            map.beatmap_id = beatmap_id;
+        }
+
+        if let Some(beatmap_set_id) = self.beatmap_set_id {
+            map.beatmap_set_id = beatmap_set_id;
+        }
+
+        if let Some(tag) = &self.tag {
+            let tag = tag.replace("{rate}", formatted_rate);
+            // N'ajoute pas deux fois le même tag pour garder un résultat stable
+            if !map.tags.contains(&tag) {
+                if map.tags.trim().is_empty() {
+                    map.tags = tag;
+                } else {
+                    map.tags = format!("{} {}", map.tags.trim_end(), tag);
+                }
+            }
+        }
+
+        if let Some(suffix) = &self.creator_suffix {
+            if !map.creator.ends_with(suffix.as_str()) {
+                map.creator.push_str(suffix);
+            }
+        }
+    }
+}
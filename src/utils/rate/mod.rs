@@ -1,5 +1,9 @@
 pub mod beatmap_processor;
 pub mod compression;
+pub mod difficulty;
 pub mod file_manager;
 pub mod hash;
+pub mod metadata;
+pub mod options;
+pub mod osz;
 pub mod rate;
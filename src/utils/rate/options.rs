@@ -0,0 +1,32 @@
+use super::compression::CompressionOptions;
+use super::difficulty::DifficultyOptions;
+use super::metadata::RateMetadata;
+use crate::utils::calculator::CalculatorOptions;
+
+/// Rates calculées par défaut, de 0.7x à 2.0x par pas de 0.1x
+pub const DEFAULT_CENTIRATES: [i32; 14] = [
+    70, 80, 90, 100, 110, 120, 130, 140, 150, 160, 170, 180, 190, 200,
+];
+
+/// Options appliquées lors de la génération des fichiers de rate
+#[derive(Debug, Clone)]
+pub struct RateOptions {
+    /// Rates à calculer, en centièmes (minacalc ne couvre que 0.7x à 2.0x)
+    pub centirates: Vec<i32>,
+    pub metadata: RateMetadata,
+    pub difficulty: DifficultyOptions,
+    pub compression: CompressionOptions,
+    pub calculators: CalculatorOptions,
+}
+
+impl Default for RateOptions {
+    fn default() -> Self {
+        Self {
+            centirates: DEFAULT_CENTIRATES.to_vec(),
+            metadata: RateMetadata::default(),
+            difficulty: DifficultyOptions::default(),
+            compression: CompressionOptions::default(),
+            calculators: CalculatorOptions::default(),
+        }
+    }
+}
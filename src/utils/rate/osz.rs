@@ -0,0 +1,72 @@
+use rosu_map::Beatmap;
+use std::collections::HashSet;
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Construit une archive `.osz` (zip) à partir de difficultés et de fichiers annexes
+pub struct OszBuilder {
+    writer: ZipWriter<Cursor<Vec<u8>>>,
+    entries: HashSet<String>,
+}
+
+impl OszBuilder {
+    pub fn new() -> Self {
+        Self {
+            writer: ZipWriter::new(Cursor::new(Vec::new())),
+            entries: HashSet::new(),
+        }
+    }
+
+    /// Ajoute une difficulté encodée, nommée comme le ferait osu! (`Artist - Title (Creator) [Version].osu`)
+    pub fn add_beatmap(
+        &mut self,
+        map: &Beatmap,
+        encoded: &[u8],
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        self.add_file(&Self::osu_file_name(map), encoded)
+    }
+
+    /// Ajoute un fichier à l'archive, ignore les doublons (retourne false dans ce cas)
+    pub fn add_file(
+        &mut self,
+        name: &str,
+        data: &[u8],
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        if !self.entries.insert(name.to_string()) {
+            return Ok(false);
+        }
+
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        self.writer.start_file(name, options)?;
+        self.writer.write_all(data)?;
+        Ok(true)
+    }
+
+    /// Finalise l'archive et retourne son contenu
+    pub fn finish(self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let cursor = self.writer.finish()?;
+        Ok(cursor.into_inner())
+    }
+
+    /// Nom de fichier `.osu` d'une difficulté, sans caractères interdits
+    pub fn osu_file_name(map: &Beatmap) -> String {
+        let name = format!(
+            "{} - {} ({}) [{}].osu",
+            map.artist, map.title, map.creator, map.version
+        );
+
+        name.chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+                c => c,
+            })
+            .collect()
+    }
+}
+
+impl Default for OszBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
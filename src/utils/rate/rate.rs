@@ -2,31 +2,78 @@ use super::beatmap_processor::BeatmapProcessor;
 use super::compression::CompressionManager;
 use super::file_manager::FileManager;
 use super::hash::hash_md5;
+use super::options::RateOptions;
 use rosu_map::Beatmap;
 
+/// Résultat du traitement d'une rate
+#[derive(Debug, Clone)]
+pub struct ProcessedRate {
+    pub hash: String,
+    /// OD effectif de la rate après ajustement
+    pub od: f32,
+    /// HP effectif de la rate après ajustement
+    pub hp: f32,
+}
 
-/// Traite une seule rate : clone le beatmap, applique la rate, compresse et sauvegarde
-pub fn process_single_rate(centirate: i64, maps: &Beatmap, beatmap_id: i32) -> String {
+/// Beatmap d'une rate encodée, avant compression
+#[derive(Debug, Clone)]
+pub struct RenderedRate {
+    pub content: String,
+    pub processed: ProcessedRate,
+}
+
+/// Clone le beatmap, applique la rate et réécrit les métadonnées, sans rien écrire sur disque
+pub fn render_rate(centirate: i64, maps: &Beatmap, options: &RateOptions) -> RenderedRate {
     // 1. Cloner et traiter le beatmap avec le rate
     let mut processed_map = maps.clone();
-    BeatmapProcessor::apply_rate_to_beatmap(centirate, &mut processed_map);
+    BeatmapProcessor::apply_rate_to_beatmap(centirate, &mut processed_map, &options.difficulty);
+
+    // 2. Réécrire les métadonnées pour distinguer la rate de l'originale
+    options.metadata.apply(
+        &BeatmapProcessor::format_rate(centirate),
+        &mut processed_map,
+    );
 
-    // 2. Encoder le beatmap en string
-    let encoded = processed_map.encode_to_string().unwrap();
+    // 3. Encoder le beatmap en string
+    let content = processed_map.encode_to_string().unwrap();
 
-    // 3. Générer le hash
-    let hash = hash_md5(&encoded).unwrap();
+    // 4. Générer le hash
+    let hash = hash_md5(&content).unwrap();
+
+    RenderedRate {
+        content,
+        processed: ProcessedRate {
+            hash,
+            od: processed_map.overall_difficulty,
+            hp: processed_map.hp_drain_rate,
+        },
+    }
+}
+
+/// Traite une seule rate : clone le beatmap, applique la rate, compresse et sauvegarde
+pub fn process_single_rate(
+    centirate: i64,
+    maps: &Beatmap,
+    storage_key: &str,
+    options: &RateOptions,
+) -> ProcessedRate {
+    let rendered = render_rate(centirate, maps, options);
 
-    // 4. Compresser les données
-    let compression_result = CompressionManager::compress_string(&encoded).unwrap();
+    // Compresser les données
+    let compression_result =
+        CompressionManager::compress_string(&rendered.content, &options.compression).unwrap();
 
-    // 5. Sauvegarder le fichier compressé
-    let _file_path =
-        FileManager::save_compressed_file(beatmap_id, &hash, &compression_result.compressed_data)
-            .unwrap();
+    // Sauvegarder le fichier compressé
+    let _file_path = FileManager::save_compressed_file(
+        storage_key,
+        &rendered.processed.hash,
+        compression_result.codec,
+        &compression_result.compressed_data,
+    )
+    .unwrap();
 
-    // 6. Logger les détails
+    // Logger les détails
     compression_result.log_compression_details(centirate as f64 / 100.0);
 
-    hash
+    rendered.processed
 }
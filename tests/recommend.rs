@@ -0,0 +1,234 @@
+//! Ranking of `core::query::recommend` against Postgres
+//!
+//! Each test rates its maps under a rating type of its own, so other rows never show up.
+//! Needs a dedicated database in `DATABASE_URL`: `cargo test --test recommend -- --ignored`
+
+use db::db::DatabaseManager;
+use pendora::config::{Config, ConfigSource};
+use pendora::core::query::recommend::{recommend, RecommendRequest, SkillsetVector};
+use pendora::core::startup::connect_database;
+use pendora::store::tables::{BEATMAP, BEATMAPSET, BEATMAP_MANIA_RATING, BEATMAP_RATING, RATES};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+async fn database() -> DatabaseManager {
+    let source = ConfigSource::new(None, &["DATABASE_CONNECT_MAX_ATTEMPTS=1".to_string()]).unwrap();
+    let mut config = Config::from_source(&source).unwrap();
+    connect_database(&mut config.database, &config.database_options)
+        .await
+        .expect("DATABASE_URL must point to a reachable database");
+    config.database
+}
+
+/// Rating type and hash prefix unique to one test run, short enough for both columns
+fn unique_rating_type() -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let micros = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_micros();
+    format!(
+        "t{}{:09}",
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+        micros % 1_000_000_000
+    )
+}
+
+/// Insert a processed 1.0x rate with these skillsets, in the order of `SkillsetVector`
+async fn insert_rate(
+    database: &DatabaseManager,
+    rating_type: &str,
+    hash: &str,
+    skillsets: [f64; 7],
+) {
+    insert_rate_with_pattern(database, rating_type, hash, skillsets, &[]).await;
+}
+
+async fn insert_rate_with_pattern(
+    database: &DatabaseManager,
+    rating_type: &str,
+    hash: &str,
+    skillsets: [f64; 7],
+    main_pattern: &[&str],
+) {
+    let sql = format!(
+        "WITH s AS (INSERT INTO {beatmapset} \
+             (artist, title, creator, has_video, has_storyboard, is_explicit, is_featured) \
+             VALUES ('Pendora', 'Recommend', 'pendora', false, false, false, false) RETURNING id), \
+         b AS (INSERT INTO {beatmap} \
+             (beatmapset_id, difficulty, count_circles, count_sliders, count_spinners, \
+              max_combo, main_pattern, cs, ar, od, hp, mode, status) \
+             SELECT id, $1, 0, 0, 0, 0, $11, 4, 5, 8, 8, 3, 'ranked' FROM s RETURNING id), \
+         r AS (INSERT INTO {rates} (beatmap_id, osu_hash, centirate, drain_time, total_time, bpm) \
+             SELECT id, $1, 100, 60, 60, 150 FROM b RETURNING id), \
+         br AS (INSERT INTO {rating} (rates_id, rating, rating_type) \
+             SELECT id, $2, $3 FROM r RETURNING id) \
+         INSERT INTO {mania} \
+             (rating_id, stream, jumpstream, handstream, stamina, jackspeed, chordjack, technical) \
+             SELECT id, $4, $5, $6, $7, $8, $9, $10 FROM br",
+        beatmapset = BEATMAPSET,
+        beatmap = BEATMAP,
+        rates = RATES,
+        rating = BEATMAP_RATING,
+        mania = BEATMAP_MANIA_RATING,
+    );
+
+    let overall = skillsets.iter().cloned().fold(0.0, f64::max);
+    let mut query = sqlx::query(&sql).bind(hash).bind(overall).bind(rating_type);
+    for value in skillsets {
+        query = query.bind(value);
+    }
+    query = query.bind(serde_json::json!(main_pattern));
+    query.execute(database.get_pool()).await.unwrap();
+}
+
+fn request(rating_type: &str) -> RecommendRequest {
+    RecommendRequest {
+        rating_type: rating_type.to_string(),
+        target: None,
+        recent_hashes: Vec::new(),
+        weights: SkillsetVector::default(),
+        keys: None,
+        pattern: None,
+        limit: None,
+    }
+}
+
+async fn ranked_hashes(database: &DatabaseManager, request: &RecommendRequest) -> Vec<String> {
+    recommend(database, request)
+        .await
+        .unwrap()
+        .results
+        .into_iter()
+        .map(|recommendation| recommendation.hit.osu_hash)
+        .collect()
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres database in DATABASE_URL"]
+async fn closest_rates_rank_first() {
+    let database = database().await;
+    let rating_type = unique_rating_type();
+    let far = format!("{}-far", rating_type);
+    let near = format!("{}-near", rating_type);
+    let exact = format!("{}-exact", rating_type);
+    insert_rate(&database, &rating_type, &far, [30.0; 7]).await;
+    insert_rate(&database, &rating_type, &near, [22.0; 7]).await;
+    insert_rate(&database, &rating_type, &exact, [20.0; 7]).await;
+
+    let mut request = request(&rating_type);
+    request.target = Some(SkillsetVector::splat(20.0));
+    let recommendations = recommend(&database, &request).await.unwrap();
+
+    let hashes: Vec<_> = recommendations
+        .results
+        .iter()
+        .map(|r| r.hit.osu_hash.clone())
+        .collect();
+    assert_eq!(hashes, [exact, near, far]);
+    assert!(recommendations.results[0].distance.abs() < 1e-6);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres database in DATABASE_URL"]
+async fn zero_weight_ignores_a_skillset() {
+    let database = database().await;
+    let rating_type = unique_rating_type();
+    let off_stream = format!("{}-stream", rating_type);
+    let off_technical = format!("{}-technical", rating_type);
+    let mut stream = [20.0; 7];
+    stream[0] = 26.0;
+    let mut technical = [20.0; 7];
+    technical[6] = 24.0;
+    insert_rate(&database, &rating_type, &off_stream, stream).await;
+    insert_rate(&database, &rating_type, &off_technical, technical).await;
+
+    let mut request = request(&rating_type);
+    request.target = Some(SkillsetVector::splat(20.0));
+    assert_eq!(
+        ranked_hashes(&database, &request).await,
+        [off_technical.clone(), off_stream.clone()]
+    );
+
+    request.weights.stream = 0.0;
+    assert_eq!(
+        ranked_hashes(&database, &request).await,
+        [off_stream, off_technical]
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres database in DATABASE_URL"]
+async fn recent_hashes_set_the_target_and_are_excluded() {
+    let database = database().await;
+    let rating_type = unique_rating_type();
+    let played = format!("{}-played", rating_type);
+    let similar = format!("{}-similar", rating_type);
+    let different = format!("{}-different", rating_type);
+    insert_rate(&database, &rating_type, &played, [18.0; 7]).await;
+    insert_rate(&database, &rating_type, &different, [28.0; 7]).await;
+    insert_rate(&database, &rating_type, &similar, [19.0; 7]).await;
+
+    let mut request = request(&rating_type);
+    request.recent_hashes = vec![played];
+    let recommendations = recommend(&database, &request).await.unwrap();
+
+    assert_eq!(recommendations.target, SkillsetVector::splat(18.0));
+    let hashes: Vec<_> = recommendations
+        .results
+        .into_iter()
+        .map(|r| r.hit.osu_hash)
+        .collect();
+    assert_eq!(hashes, [similar, different]);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres database in DATABASE_URL"]
+async fn limit_keeps_the_closest() {
+    let database = database().await;
+    let rating_type = unique_rating_type();
+    for i in 0..5 {
+        let hash = format!("{}-{}", rating_type, i);
+        insert_rate(&database, &rating_type, &hash, [20.0 + i as f64; 7]).await;
+    }
+
+    let mut request = request(&rating_type);
+    request.target = Some(SkillsetVector::splat(20.0));
+    request.limit = Some(2);
+
+    assert_eq!(
+        ranked_hashes(&database, &request).await,
+        [format!("{}-0", rating_type), format!("{}-1", rating_type)]
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres database in DATABASE_URL"]
+async fn pattern_keeps_matching_maps_only() {
+    let database = database().await;
+    let rating_type = unique_rating_type();
+    let jumpstream = format!("{}-js", rating_type);
+    let ln = format!("{}-ln", rating_type);
+    insert_rate_with_pattern(
+        &database,
+        &rating_type,
+        &jumpstream,
+        [20.0; 7],
+        &["jumpstream", "handstream"],
+    )
+    .await;
+    insert_rate_with_pattern(
+        &database,
+        &rating_type,
+        &ln,
+        [21.0; 7],
+        &["LN", "technical"],
+    )
+    .await;
+
+    let mut request = request(&rating_type);
+    request.target = Some(SkillsetVector::splat(20.0));
+    request.pattern = Some("LN".to_string());
+
+    assert_eq!(ranked_hashes(&database, &request).await, [ln]);
+}
@@ -0,0 +1,201 @@
+//! End-to-end runs of the beatmap worker against Postgres, the fixture osu! API and a
+//! local `.osu` file
+//!
+//! Needs a dedicated database in `DATABASE_URL`: `cargo test --test worker -- --ignored`
+
+use db::models::beatmaps::beatmap::BeatmapRow;
+use db::models::other::failed_query::FailedQueryRow;
+use pendora::api::fixture::FixtureOsuApi;
+use pendora::config::{Config, ConfigSource};
+use pendora::core::enqueue::enqueue_hash;
+use pendora::core::notify::NoopNotifier;
+use pendora::core::shutdown::Shutdown;
+use pendora::core::startup::connect_database;
+use pendora::core::worker::BeatmapWorker;
+use pendora::utils::rate::file_manager::FileManager;
+use pendora::utils::rate::hash::hash_md5;
+use serde_json::Value;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/worker");
+const PROCESS_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A worker takes any pending hash, so two tests must not run one at the same time
+static QUEUE: Mutex<()> = Mutex::const_new(());
+
+/// Fixture osu! API serving one beatmap, copied with fresh ids and a hash of its own
+struct Fixture {
+    dir: PathBuf,
+    map_id: u32,
+    osu_map: String,
+    checksum: String,
+}
+
+impl Fixture {
+    fn create() -> Self {
+        // Far above real osu! ids and different on every run, earlier rows are never reused
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        let map_id = 1_000_000_000 + (millis % 1_000_000_000) as u32;
+        let dir = std::env::temp_dir().join(format!("pendora-fixture-{}", map_id));
+
+        let version = format!("4K Fixture {}", map_id);
+        let osu_map = std::fs::read_to_string(Path::new(FIXTURES).join("map.osu"))
+            .unwrap()
+            .replace("Version:4K Fixture", &format!("Version:{}", version));
+        let checksum = hash_md5(&osu_map).unwrap();
+
+        write_file(&dir.join("osu").join(format!("{}.osu", map_id)), &osu_map);
+        write_json(
+            &dir.join("beatmaps").join(format!("{}.json", map_id)),
+            "beatmap.json",
+            |beatmap| {
+                beatmap["id"] = map_id.into();
+                beatmap["beatmapset_id"] = map_id.into();
+                beatmap["checksum"] = checksum.clone().into();
+                beatmap["version"] = version.clone().into();
+            },
+        );
+        write_json(
+            &dir.join("beatmapsets").join(format!("{}.json", map_id)),
+            "beatmapset.json",
+            |beatmapset| beatmapset["id"] = map_id.into(),
+        );
+
+        Self {
+            dir,
+            map_id,
+            osu_map,
+            checksum,
+        }
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn write_file(path: &Path, content: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn write_json(path: &Path, template: &str, edit: impl FnOnce(&mut Value)) {
+    let data = std::fs::read(Path::new(FIXTURES).join(template)).unwrap();
+    let mut value: Value = serde_json::from_slice(&data).unwrap();
+    edit(&mut value);
+    write_file(path, &serde_json::to_string_pretty(&value).unwrap());
+}
+
+/// Worker on the test database, rate files under the temp dir
+async fn worker(fixture: &Fixture) -> Arc<BeatmapWorker> {
+    let storage_root = std::env::temp_dir().join("pendora-worker-tests");
+    let source = ConfigSource::new(
+        None,
+        &[
+            format!("STORAGE_BEATMAP_ROOT={}", storage_root.display()),
+            "WORKER_POLL_INTERVAL_SECS=1".to_string(),
+            "DATABASE_CONNECT_MAX_ATTEMPTS=1".to_string(),
+        ],
+    )
+    .unwrap();
+    let mut config = Config::from_source(&source).unwrap();
+    FileManager::init(&config.storage);
+    connect_database(&mut config.database, &config.database_options)
+        .await
+        .expect("DATABASE_URL must point to a reachable database");
+
+    Arc::new(BeatmapWorker {
+        config,
+        osu_api_service: Arc::new(FixtureOsuApi::new(&fixture.dir)),
+        notifier: Arc::new(NoopNotifier),
+    })
+}
+
+/// Run the worker until `check` holds, then stop it and wait for it to return
+async fn run_until<F, Fut>(worker: &Arc<BeatmapWorker>, mut check: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let (stop, shutdown) = Shutdown::manual();
+    let running = {
+        let worker = worker.clone();
+        tokio::spawn(async move { worker.start(shutdown).await })
+    };
+
+    let reached = tokio::time::timeout(PROCESS_TIMEOUT, async {
+        while !check().await {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    })
+    .await;
+
+    stop.send(true).unwrap();
+    running.await.unwrap().unwrap();
+    reached.expect("worker did not reach the expected state in time");
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres database in DATABASE_URL"]
+async fn worker_rates_and_inserts_an_enqueued_beatmap() {
+    let _queue = QUEUE.lock().await;
+    let fixture = Fixture::create();
+    let worker = worker(&fixture).await;
+    let pool = worker.config.database.get_pool();
+    let map_id = fixture.map_id as i32;
+
+    enqueue_hash(&worker.config.database, &fixture.checksum)
+        .await
+        .unwrap();
+    run_until(&worker, || async move {
+        BeatmapRow::find_by_osu_id(pool, map_id)
+            .await
+            .unwrap()
+            .is_some()
+    })
+    .await;
+
+    let beatmap = BeatmapRow::find_by_osu_id(pool, map_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(beatmap.difficulty, format!("4K Fixture {}", map_id));
+    let storage_key = map_id.to_string();
+    assert!(!FileManager::list_rate_files(&storage_key)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        FileManager::load_original(&storage_key).unwrap().as_deref(),
+        Some(fixture.osu_map.as_str())
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres database in DATABASE_URL"]
+async fn worker_records_a_hash_unknown_to_the_api_as_failed() {
+    let _queue = QUEUE.lock().await;
+    let fixture = Fixture::create();
+    let worker = worker(&fixture).await;
+    let pool = worker.config.database.get_pool();
+    let unknown = hash_md5(&format!("unknown {}", fixture.map_id)).unwrap();
+
+    enqueue_hash(&worker.config.database, &unknown)
+        .await
+        .unwrap();
+    let unknown = unknown.as_str();
+    run_until(&worker, || async move {
+        FailedQueryRow::exists_by_hash(pool, unknown).await.unwrap()
+    })
+    .await;
+
+    assert!(!BeatmapRow::exists_by_hash(pool, unknown).await.unwrap());
+}